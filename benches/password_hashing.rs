@@ -0,0 +1,419 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rustapi::application::auth::api::auth_service::{
+    AuthService, BreachedPasswordMode, DefaultAuthService,
+};
+use rustapi::application::auth::spi::email_verification_repository::EmailVerificationRepository;
+use rustapi::application::auth::spi::magic_link_repository::MagicLinkRepository;
+use rustapi::application::auth::spi::oauth_identity_repository::OAuthIdentityRepository;
+use rustapi::application::auth::spi::password_reset_repository::PasswordResetRepository;
+use rustapi::application::auth::spi::pending_email_change_repository::PendingEmailChangeRepository;
+use rustapi::application::auth::spi::refresh_token_repository::RefreshTokenRepository;
+use rustapi::application::user::api::user_service::DefaultUserService;
+use rustapi::application::user::password_hashing::PasswordHashingLimiter;
+use rustapi::application::user::spi::user_repository::UserRepository;
+use rustapi::domain::clock::SystemClock;
+use rustapi::domain::common::DateTimeUtc;
+use rustapi::domain::email_change::PendingEmailChange;
+use rustapi::domain::email_verification::EmailVerificationToken;
+use rustapi::domain::lockout::LockoutPolicy;
+use rustapi::domain::magic_link::MagicLinkToken;
+use rustapi::domain::oauth::OAuthIdentity;
+use rustapi::domain::password::{Argon2Hasher, BcryptHasher, PasswordIdentifierPolicy, PasswordNormalization};
+use rustapi::domain::password_reset::PasswordResetToken;
+use rustapi::domain::refresh_token::RefreshToken;
+use rustapi::domain::user::User;
+use rustapi::infrastructure::event_bus::BroadcastEventBus;
+use rustapi::infrastructure::pwned_passwords::PwnedPasswordsClient;
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+const EMAIL: &str = "john.doe@example.com";
+const PASSWORD: &str = "securePassword123!";
+
+#[derive(Default)]
+struct InMemoryUserRepository {
+    users: RwLock<HashMap<String, User>>,
+}
+
+#[async_trait::async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn find_by_email(&self, email: &str) -> anyhow::Result<Option<User>> {
+        let users = self.users.read().unwrap();
+        Ok(users.values().find(|user| user.email == email).cloned())
+    }
+
+    async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<User>> {
+        Ok(self.users.read().unwrap().get(id).cloned())
+    }
+
+    async fn find_by_email_including_deleted(&self, email: &str) -> anyhow::Result<Option<User>> {
+        self.find_by_email(email).await
+    }
+
+    async fn find_by_id_including_deleted(&self, id: &str) -> anyhow::Result<Option<User>> {
+        self.find_by_id(id).await
+    }
+
+    async fn save(&self, user: User) -> anyhow::Result<User> {
+        let mut users = self.users.write().unwrap();
+        users.insert(user.id.clone(), user.clone());
+        Ok(user)
+    }
+
+    async fn update(&self, user: User) -> anyhow::Result<Option<User>> {
+        self.save(user).await.map(Some)
+    }
+
+    async fn replace_password_hash(
+        &self,
+        id: &str,
+        current_hash: &str,
+        new_hash: &str,
+    ) -> anyhow::Result<bool> {
+        let mut users = self.users.write().unwrap();
+        match users.get_mut(id) {
+            Some(user) if user.password.as_deref() == Some(current_hash) => {
+                user.password = Some(new_hash.to_string());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn increment_failed_logins(&self, id: &str) -> anyhow::Result<Option<i32>> {
+        let mut users = self.users.write().unwrap();
+        Ok(users.get_mut(id).map(|user| {
+            user.failed_login_attempts += 1;
+            user.failed_login_attempts
+        }))
+    }
+
+    async fn lock_until(&self, id: &str, until: DateTimeUtc) -> anyhow::Result<()> {
+        if let Some(user) = self.users.write().unwrap().get_mut(id) {
+            user.locked_until = Some(until);
+        }
+        Ok(())
+    }
+
+    async fn clear_failed_logins(&self, id: &str) -> anyhow::Result<()> {
+        if let Some(user) = self.users.write().unwrap().get_mut(id) {
+            user.failed_login_attempts = 0;
+            user.locked_until = None;
+        }
+        Ok(())
+    }
+
+    async fn soft_delete(&self, id: &str, _at: DateTimeUtc) -> anyhow::Result<()> {
+        self.delete(id).await
+    }
+
+    async fn delete(&self, id: &str) -> anyhow::Result<()> {
+        self.users.write().unwrap().remove(id);
+        Ok(())
+    }
+
+    async fn count(&self) -> anyhow::Result<u64> {
+        let users = self.users.read().unwrap();
+        Ok(users.values().filter(|user| !user.is_deleted()).count() as u64)
+    }
+}
+
+struct NoopPasswordResetRepository;
+
+#[async_trait::async_trait]
+impl PasswordResetRepository for NoopPasswordResetRepository {
+    async fn save(&self, token: PasswordResetToken) -> anyhow::Result<PasswordResetToken> {
+        Ok(token)
+    }
+
+    async fn find_by_token_hash(&self, _token_hash: &str) -> anyhow::Result<Option<PasswordResetToken>> {
+        Ok(None)
+    }
+
+    async fn mark_used(&self, _id: &str, _used_at: DateTimeUtc) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn invalidate_for_user(&self, _user_id: &str, _at: DateTimeUtc) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+struct NoopRefreshTokenRepository;
+
+#[async_trait::async_trait]
+impl RefreshTokenRepository for NoopRefreshTokenRepository {
+    async fn save(&self, token: RefreshToken) -> anyhow::Result<RefreshToken> {
+        Ok(token)
+    }
+
+    async fn find_by_token_hash(&self, _token_hash: &str) -> anyhow::Result<Option<RefreshToken>> {
+        Ok(None)
+    }
+
+    async fn revoke(&self, _id: &str, _revoked_at: DateTimeUtc) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn revoke_family(&self, _family_id: &str, _revoked_at: DateTimeUtc) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn revoke_for_user(&self, _user_id: &str, _revoked_at: DateTimeUtc) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+struct NoopEmailVerificationRepository;
+
+#[async_trait::async_trait]
+impl EmailVerificationRepository for NoopEmailVerificationRepository {
+    async fn save(&self, token: EmailVerificationToken) -> anyhow::Result<EmailVerificationToken> {
+        Ok(token)
+    }
+
+    async fn find_by_token_hash(&self, _token_hash: &str) -> anyhow::Result<Option<EmailVerificationToken>> {
+        Ok(None)
+    }
+
+    async fn mark_used(&self, _id: &str, _used_at: DateTimeUtc) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn invalidate_for_user(&self, _user_id: &str, _at: DateTimeUtc) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+struct NoopOAuthIdentityRepository;
+
+#[async_trait::async_trait]
+impl OAuthIdentityRepository for NoopOAuthIdentityRepository {
+    async fn find(&self, _provider: &str, _subject: &str) -> anyhow::Result<Option<OAuthIdentity>> {
+        Ok(None)
+    }
+
+    async fn save(&self, identity: OAuthIdentity) -> anyhow::Result<OAuthIdentity> {
+        Ok(identity)
+    }
+}
+
+struct NoopMagicLinkRepository;
+
+#[async_trait::async_trait]
+impl MagicLinkRepository for NoopMagicLinkRepository {
+    async fn save(&self, token: MagicLinkToken) -> anyhow::Result<MagicLinkToken> {
+        Ok(token)
+    }
+
+    async fn find_by_token_hash(&self, _token_hash: &str) -> anyhow::Result<Option<MagicLinkToken>> {
+        Ok(None)
+    }
+
+    async fn mark_used(&self, _id: &str, _used_at: DateTimeUtc) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn invalidate_for_user(&self, _user_id: &str, _at: DateTimeUtc) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+struct NoopPendingEmailChangeRepository;
+
+#[async_trait::async_trait]
+impl PendingEmailChangeRepository for NoopPendingEmailChangeRepository {
+    async fn save(&self, change: PendingEmailChange) -> anyhow::Result<PendingEmailChange> {
+        Ok(change)
+    }
+
+    async fn find_by_token_hash(&self, _token_hash: &str) -> anyhow::Result<Option<PendingEmailChange>> {
+        Ok(None)
+    }
+
+    async fn mark_used(&self, _id: &str, _used_at: DateTimeUtc) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    async fn invalidate_for_user(&self, _user_id: &str, _at: DateTimeUtc) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+fn bench_bcrypt_costs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bcrypt");
+    group.sample_size(10);
+    for cost in [4, 8, 10, bcrypt::DEFAULT_COST] {
+        let hash = bcrypt::hash(PASSWORD, cost).unwrap();
+        group.bench_with_input(BenchmarkId::new("hash", cost), &cost, |b, &cost| {
+            b.iter(|| bcrypt::hash(black_box(PASSWORD), cost).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("verify", cost), &hash, |b, hash| {
+            b.iter(|| bcrypt::verify(black_box(PASSWORD), hash).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn bench_user_password(c: &mut Criterion) {
+    let normalization = PasswordNormalization::default();
+    let user = User::create_new_user(EMAIL, PASSWORD, &BcryptHasher::default(), &normalization, &PasswordIdentifierPolicy::default(), &SystemClock).unwrap();
+
+    let mut group = c.benchmark_group("user");
+    group.sample_size(10);
+    group.bench_function("hash_password", |b| {
+        b.iter(|| User::hash_password(black_box(PASSWORD), &BcryptHasher::default(), &normalization).unwrap())
+    });
+    group.bench_function("is_password_match", |b| {
+        b.iter(|| user.is_password_match(black_box(PASSWORD), &normalization).unwrap())
+    });
+    group.bench_function("hash_password_argon2", |b| {
+        b.iter(|| User::hash_password(black_box(PASSWORD), &Argon2Hasher::default(), &normalization).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_login(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let user_repository = Arc::new(InMemoryUserRepository::default());
+    let user_service = Arc::new(DefaultUserService {
+        user_repository: user_repository.clone(),
+        user_read_repository: user_repository,
+        clock: Arc::new(SystemClock),
+        password_hashing: PasswordHashingLimiter::default(),
+        password_hasher: Arc::new(BcryptHasher::default()),
+        password_normalization: PasswordNormalization::default(),
+        password_identifier_policy: PasswordIdentifierPolicy::default(),
+        lockout_policy: LockoutPolicy::disabled(),
+        deletion_grace: chrono::Duration::zero(),
+    });
+    let auth_service = DefaultAuthService {
+        user_service,
+        event_publisher: Arc::new(BroadcastEventBus::new()),
+        clock: Arc::new(SystemClock),
+        disclose_email_availability: false,
+        breached_password_checker: Arc::new(PwnedPasswordsClient::from_env(reqwest::Client::new()).unwrap()),
+        breached_password_mode: BreachedPasswordMode::Off,
+        email_domain_checker: None,
+        password_reset_repository: Arc::new(NoopPasswordResetRepository),
+        email_verification: false,
+        email_verification_repository: Arc::new(NoopEmailVerificationRepository),
+        refresh_token_repository: Arc::new(NoopRefreshTokenRepository),
+        refresh_token_ttl: chrono::Duration::days(30),
+        oauth_providers: HashMap::new(),
+        oauth_identity_repository: Arc::new(NoopOAuthIdentityRepository),
+        magic_link_repository: Arc::new(NoopMagicLinkRepository),
+        pending_email_change_repository: Arc::new(NoopPendingEmailChangeRepository),
+    };
+    runtime
+        .block_on(auth_service.register(EMAIL, PASSWORD))
+        .unwrap();
+
+    let mut group = c.benchmark_group("auth");
+    group.sample_size(10);
+    group.bench_function("login", |b| {
+        b.to_async(&runtime)
+            .iter(|| auth_service.login(black_box(EMAIL), black_box(PASSWORD)))
+    });
+    group.finish();
+}
+
+// Reports the slowest login of each burst, i.e. tail latency under
+// concurrent auth load with hashing offloaded to the blocking pool.
+fn bench_concurrent_login(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let user_repository = Arc::new(InMemoryUserRepository::default());
+    let user_service = Arc::new(DefaultUserService {
+        user_repository: user_repository.clone(),
+        user_read_repository: user_repository,
+        clock: Arc::new(SystemClock),
+        password_hashing: PasswordHashingLimiter::default(),
+        password_hasher: Arc::new(BcryptHasher::default()),
+        password_normalization: PasswordNormalization::default(),
+        password_identifier_policy: PasswordIdentifierPolicy::default(),
+        lockout_policy: LockoutPolicy::disabled(),
+        deletion_grace: chrono::Duration::zero(),
+    });
+    let auth_service = Arc::new(DefaultAuthService {
+        user_service,
+        event_publisher: Arc::new(BroadcastEventBus::new()),
+        clock: Arc::new(SystemClock),
+        disclose_email_availability: false,
+        breached_password_checker: Arc::new(PwnedPasswordsClient::from_env(reqwest::Client::new()).unwrap()),
+        breached_password_mode: BreachedPasswordMode::Off,
+        email_domain_checker: None,
+        password_reset_repository: Arc::new(NoopPasswordResetRepository),
+        email_verification: false,
+        email_verification_repository: Arc::new(NoopEmailVerificationRepository),
+        refresh_token_repository: Arc::new(NoopRefreshTokenRepository),
+        refresh_token_ttl: chrono::Duration::days(30),
+        oauth_providers: HashMap::new(),
+        oauth_identity_repository: Arc::new(NoopOAuthIdentityRepository),
+        magic_link_repository: Arc::new(NoopMagicLinkRepository),
+        pending_email_change_repository: Arc::new(NoopPendingEmailChangeRepository),
+    });
+    runtime
+        .block_on(auth_service.register(EMAIL, PASSWORD))
+        .unwrap();
+
+    let mut group = c.benchmark_group("auth/concurrent_login_max");
+    group.sample_size(10);
+    for concurrency in [1, 8, 32] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(concurrency),
+            &concurrency,
+            |b, &concurrency| {
+                b.iter_custom(|iters| {
+                    runtime.block_on(async {
+                        let mut total = Duration::ZERO;
+                        for _ in 0..iters {
+                            let mut tasks = tokio::task::JoinSet::new();
+                            for _ in 0..concurrency {
+                                let auth_service = auth_service.clone();
+                                tasks.spawn(async move {
+                                    let start = Instant::now();
+                                    auth_service.login(EMAIL, PASSWORD).await.unwrap();
+                                    start.elapsed()
+                                });
+                            }
+                            let slowest = tasks.join_all().await.into_iter().max();
+                            total += slowest.unwrap_or_default();
+                        }
+                        total
+                    })
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_bcrypt_costs,
+    bench_user_password,
+    bench_login,
+    bench_concurrent_login
+);
+criterion_main!(benches);
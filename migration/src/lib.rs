@@ -17,12 +17,34 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_init_table;
+mod m20220101_000002_create_user_sessions_table;
+mod m20220101_000003_add_user_locale_and_timezone;
+mod m20220101_000004_create_password_reset_tokens_table;
+mod m20220101_000005_add_email_verification;
+mod m20220101_000006_create_refresh_tokens_table;
+mod m20220101_000007_add_user_lockout;
+mod m20220101_000008_create_oauth_identities_table;
+mod m20220101_000009_create_magic_link_tokens_table;
+mod m20220101_000010_create_pending_email_changes_table;
+mod m20220101_000011_add_user_soft_delete;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_init_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_init_table::Migration),
+            Box::new(m20220101_000002_create_user_sessions_table::Migration),
+            Box::new(m20220101_000003_add_user_locale_and_timezone::Migration),
+            Box::new(m20220101_000004_create_password_reset_tokens_table::Migration),
+            Box::new(m20220101_000005_add_email_verification::Migration),
+            Box::new(m20220101_000006_create_refresh_tokens_table::Migration),
+            Box::new(m20220101_000007_add_user_lockout::Migration),
+            Box::new(m20220101_000008_create_oauth_identities_table::Migration),
+            Box::new(m20220101_000009_create_magic_link_tokens_table::Migration),
+            Box::new(m20220101_000010_create_pending_email_changes_table::Migration),
+            Box::new(m20220101_000011_add_user_soft_delete::Migration),
+        ]
     }
 }
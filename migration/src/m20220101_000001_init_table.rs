@@ -33,7 +33,7 @@ impl MigrationTrait for Migration {
             updated_at TIMESTAMPTZ             NOT NULL DEFAULT NOW()
         )
         "#;
-        db.execute_unprepared(&sql).await?;
+        db.execute_unprepared(sql).await?;
         Ok(())
     }
 
@@ -42,7 +42,7 @@ impl MigrationTrait for Migration {
         let sql = r#"
         DROP TABLE IF EXISTS "users"
         "#;
-        db.execute_unprepared(&sql).await?;
+        db.execute_unprepared(sql).await?;
         Ok(())
     }
 }
@@ -13,14 +13,45 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use crate::application::auth::spi::breached_password_checker::BreachedPasswordChecker;
+use crate::application::auth::spi::email_domain_checker::EmailDomainChecker;
+use crate::application::auth::spi::email_verification_repository::EmailVerificationRepository;
+use crate::application::auth::spi::magic_link_repository::MagicLinkRepository;
+use crate::application::auth::spi::oauth_identity_repository::OAuthIdentityRepository;
+use crate::application::auth::spi::oauth_provider::OAuthProvider;
+use crate::application::auth::spi::password_reset_repository::PasswordResetRepository;
+use crate::application::auth::spi::pending_email_change_repository::PendingEmailChangeRepository;
+use crate::application::auth::spi::refresh_token_repository::RefreshTokenRepository;
+use crate::application::event::spi::event_publisher::EventPublisher;
 use crate::application::user::api::user_service::UserService;
+use crate::domain::clock::Clock;
 use crate::domain::common::DomainError;
+use crate::domain::email_change::PendingEmailChange;
+use crate::domain::email_verification::EmailVerificationToken;
+use crate::domain::event::DomainEvent;
+use crate::domain::magic_link::MagicLinkToken;
+use crate::domain::oauth::{OAuthIdentity, OAuthUserInfo};
+use crate::domain::password_reset::PasswordResetToken;
+use crate::domain::refresh_token::RefreshToken;
+use crate::domain::secret_token::{hash_token, SecretToken, TokenKind};
 use crate::domain::user::User;
+use std::collections::HashMap;
+use std::str::FromStr;
 use std::sync::Arc;
 
 #[async_trait::async_trait]
 pub trait AuthService: Send + Sync + 'static {
     async fn register(&self, email: &str, password: &str) -> Result<User, DomainError>;
+    /// The email checks `register` runs beyond the request format.
+    async fn validate_registration_email(&self, email: &str) -> Result<(), DomainError>;
+    /// The password policy `register` enforces beyond the request format.
+    async fn validate_registration_password(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<(), DomainError>;
+    async fn check_email_availability(&self, email: &str) -> Result<Option<bool>, DomainError>;
+    async fn check_breached_password(&self, password: &str) -> Result<Option<u64>, DomainError>;
     async fn login(&self, email: &str, password: &str) -> Result<User, DomainError>;
     async fn change_password(
         &self,
@@ -28,23 +59,371 @@ pub trait AuthService: Send + Sync + 'static {
         current_password: &str,
         new_password: &str,
     ) -> Result<User, DomainError>;
+    async fn update_preferences(
+        &self,
+        user_id: &str,
+        locale: Option<String>,
+        timezone: Option<String>,
+    ) -> Result<User, DomainError>;
+    /// Succeeds whether or not the email belongs to an account, so callers
+    /// cannot use it to probe for registered addresses.
+    async fn request_password_reset(&self, email: &str) -> Result<(), DomainError>;
+    async fn reset_password(&self, token: &str, new_password: &str) -> Result<User, DomainError>;
+    async fn verify_email(&self, token: &str) -> Result<User, DomainError>;
+    /// Starts a new refresh token family for a fresh login.
+    async fn issue_refresh_token(&self, user_id: &str) -> Result<SecretToken, DomainError>;
+    /// Exchanges a refresh token for its successor. Presenting a token that
+    /// was already rotated revokes its whole family.
+    async fn rotate_refresh_token(&self, token: &str) -> Result<(User, SecretToken), DomainError>;
+    /// Revokes every outstanding token of the given kinds. Idempotent.
+    async fn revoke_tokens(&self, user_id: &str, kinds: &[TokenKind]) -> Result<(), DomainError>;
+
+    /// Where to send the browser to sign in with `provider`.
+    fn oauth_authorization_url(&self, provider: &str, state: &str) -> Result<String, DomainError>;
+
+    /// Signs in with the code `provider` returned, linking or creating the
+    /// account on first use.
+    async fn login_with_oauth(&self, provider: &str, code: &str) -> Result<User, DomainError>;
+
+    /// Issues a single-use login link for `email`; a no-op for unknown emails.
+    async fn request_magic_link(&self, email: &str) -> Result<(), DomainError>;
+    /// Consumes a login link. Following it also proves the email.
+    async fn login_with_magic_link(&self, token: &str) -> Result<User, DomainError>;
+
+    /// Sends a confirmation link to `new_email`; the email changes only once
+    /// it is confirmed.
+    async fn request_email_change(
+        &self,
+        user_id: &str,
+        current_password: &str,
+        new_email: &str,
+    ) -> Result<(), DomainError>;
+    /// Switches to the confirmed email and revokes every outstanding token.
+    async fn confirm_email_change(&self, token: &str) -> Result<User, DomainError>;
+
+    /// Soft-deletes the account and revokes its tokens, after checking the
+    /// current password.
+    async fn delete_account(&self, user_id: &str, current_password: &str)
+    -> Result<(), DomainError>;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreachedPasswordMode {
+    Off,
+    Warn,
+    Reject,
+}
+
+impl FromStr for BreachedPasswordMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "reject" => Ok(Self::Reject),
+            _ => Err(format!("expected off, warn or reject, got {}", value)),
+        }
+    }
 }
 
 pub struct DefaultAuthService {
     pub user_service: Arc<dyn UserService>,
+    pub event_publisher: Arc<dyn EventPublisher>,
+    pub clock: Arc<dyn Clock>,
+    pub disclose_email_availability: bool,
+    pub breached_password_checker: Arc<dyn BreachedPasswordChecker>,
+    pub breached_password_mode: BreachedPasswordMode,
+    /// Set only when registration requires the email domain to accept mail.
+    pub email_domain_checker: Option<Arc<dyn EmailDomainChecker>>,
+    pub password_reset_repository: Arc<dyn PasswordResetRepository>,
+    /// When set, registration issues an email verification token.
+    pub email_verification: bool,
+    pub email_verification_repository: Arc<dyn EmailVerificationRepository>,
+    pub refresh_token_repository: Arc<dyn RefreshTokenRepository>,
+    pub refresh_token_ttl: chrono::Duration,
+    /// Keyed by provider name; empty when no provider is configured.
+    pub oauth_providers: HashMap<String, Arc<dyn OAuthProvider>>,
+    pub oauth_identity_repository: Arc<dyn OAuthIdentityRepository>,
+    pub magic_link_repository: Arc<dyn MagicLinkRepository>,
+    pub pending_email_change_repository: Arc<dyn PendingEmailChangeRepository>,
+}
+
+impl DefaultAuthService {
+    /// Credentials that would outlast a password change: a stolen refresh
+    /// token, or a link mailed before it.
+    const REVOKED_ON_PASSWORD_CHANGE: [TokenKind; 3] = [
+        TokenKind::Refresh,
+        TokenKind::PasswordReset,
+        TokenKind::MagicLink,
+    ];
+
+    fn oauth_provider(&self, provider: &str) -> Result<&Arc<dyn OAuthProvider>, DomainError> {
+        self.oauth_providers
+            .get(provider)
+            .ok_or(DomainError::NotFoundError)
+    }
+
+    /// Finds the account for an identity seen for the first time: an account
+    /// with the same email is linked only when both the provider and the
+    /// account have verified it.
+    async fn link_oauth_identity(
+        &self,
+        provider: &str,
+        info: &OAuthUserInfo,
+    ) -> Result<User, DomainError> {
+        if !info.email_verified {
+            return Err(DomainError::validation(
+                "oauth_email_not_verified_error",
+                "The identity provider has not verified this email",
+            ));
+        }
+        let user = match self.user_service.find_by_email(&info.email).await {
+            // Whoever registered an unverified account may not own the
+            // email; linking would leave their password working on it.
+            Ok(user) if user.email_verified_at.is_none() => {
+                return Err(DomainError::validation(
+                    "oauth_account_not_verified_error",
+                    "An account with this email exists but has not verified it",
+                ));
+            }
+            Ok(user) => user,
+            Err(DomainError::NotFoundError) => {
+                let user = self
+                    .user_service
+                    .create_passwordless_user(&info.email)
+                    .await?;
+                self.event_publisher.publish(DomainEvent::UserRegistered {
+                    user_id: user.id.clone(),
+                    email: user.email.clone(),
+                    occurred_at: self.clock.now(),
+                });
+                user
+            }
+            Err(e) => return Err(e),
+        };
+        self.oauth_identity_repository
+            .save(OAuthIdentity::link(
+                &user.id,
+                provider,
+                &info.subject,
+                self.clock.as_ref(),
+            ))
+            .await
+            .map_err(DomainError::from_repository)?;
+        Ok(user)
+    }
+
+    fn invalid_reset_token() -> DomainError {
+        DomainError::validation(
+            "invalid_reset_token_error",
+            "The password reset token is invalid or has expired",
+        )
+    }
+
+    // Fails open: an unanswered lookup must not block signups.
+    async fn ensure_email_domain_accepts_mail(&self, email: &str) -> Result<(), DomainError> {
+        let Some(checker) = &self.email_domain_checker else {
+            return Ok(());
+        };
+        let Some((_, domain)) = email.rsplit_once('@') else {
+            return Ok(());
+        };
+
+        match checker.accepts_mail(domain).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(DomainError::validation(
+                "email_domain_undeliverable_error",
+                "The email domain does not accept mail",
+            )),
+            Err(e) => {
+                tracing::warn!("Email domain check unavailable: {:?}", e);
+                Ok(())
+            }
+        }
+    }
+
+    fn invalid_email_change_token() -> DomainError {
+        DomainError::validation(
+            "invalid_email_change_token_error",
+            "The email change token is invalid or has expired",
+        )
+    }
+
+    fn invalid_verification_token() -> DomainError {
+        DomainError::validation(
+            "invalid_verification_token_error",
+            "The email verification token is invalid or has expired",
+        )
+    }
+
+    async fn save_refresh_token(
+        &self,
+        user_id: &str,
+        family_id: Option<&str>,
+    ) -> Result<SecretToken, DomainError> {
+        let (record, token) = RefreshToken::issue(
+            user_id,
+            family_id,
+            self.refresh_token_ttl,
+            self.clock.as_ref(),
+        );
+        self.refresh_token_repository
+            .save(record)
+            .await
+            .map_err(DomainError::from_repository)?;
+        Ok(token)
+    }
+
+    async fn revoke_refresh_token_family(&self, record: &RefreshToken) -> Result<(), DomainError> {
+        tracing::warn!(
+            "Refresh token reuse detected for user {}, revoking token family {}",
+            record.user_id,
+            record.family_id
+        );
+        self.refresh_token_repository
+            .revoke_family(&record.family_id, self.clock.now())
+            .await
+            .map_err(DomainError::from_repository)
+    }
+
+    async fn issue_email_verification(&self, user: &User) -> anyhow::Result<()> {
+        let (record, token) = EmailVerificationToken::issue(&user.id, self.clock.as_ref());
+        let record = self.email_verification_repository.save(record).await?;
+        self.event_publisher
+            .publish(DomainEvent::EmailVerificationRequested {
+                user_id: user.id.clone(),
+                email: user.email.clone(),
+                token,
+                occurred_at: record.created_at,
+            });
+        Ok(())
+    }
+
+    async fn ensure_password_not_breached(&self, password: &str) -> Result<(), DomainError> {
+        if self.breached_password_mode == BreachedPasswordMode::Off {
+            return Ok(());
+        }
+
+        match self.check_breached_password(password).await? {
+            Some(count) if count > 0 => {
+                if self.breached_password_mode == BreachedPasswordMode::Reject {
+                    return Err(DomainError::validation(
+                        "password_breached_error",
+                        "This password appears in a known data breach",
+                    ));
+                }
+                tracing::warn!("Password accepted despite appearing in {} breaches", count);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 #[async_trait::async_trait]
 impl AuthService for DefaultAuthService {
     async fn register(&self, email: &str, password: &str) -> Result<User, DomainError> {
-        self.user_service
+        self.validate_registration_email(email).await?;
+        self.validate_registration_password(email, password).await?;
+        let user = self
+            .user_service
             .create_user_if_not_exists(email, password)
-            .await
+            .await?;
+        self.event_publisher.publish(DomainEvent::UserRegistered {
+            user_id: user.id.clone(),
+            email: user.email.clone(),
+            occurred_at: self.clock.now(),
+        });
+        // The account already exists, so a failure here must not fail the
+        // registration.
+        if self.email_verification
+            && let Err(e) = self.issue_email_verification(&user).await
+        {
+            tracing::warn!("Failed to issue email verification token: {:?}", e);
+        }
+        Ok(user)
+    }
+
+    async fn validate_registration_email(&self, email: &str) -> Result<(), DomainError> {
+        self.ensure_email_domain_accepts_mail(email).await
+    }
+
+    async fn validate_registration_password(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<(), DomainError> {
+        self.user_service.check_new_password(email, password)?;
+        self.ensure_password_not_breached(password).await
+    }
+
+    async fn check_email_availability(&self, email: &str) -> Result<Option<bool>, DomainError> {
+        if !self.disclose_email_availability {
+            return Ok(None);
+        }
+
+        let registered = self.user_service.is_email_registered(email).await?;
+        Ok(Some(!registered))
+    }
+
+    async fn check_breached_password(&self, password: &str) -> Result<Option<u64>, DomainError> {
+        if self.breached_password_mode == BreachedPasswordMode::Off {
+            return Ok(None);
+        }
+
+        match self.breached_password_checker.breach_count(password).await {
+            Ok(count) => Ok(Some(count)),
+            Err(e) => {
+                tracing::warn!("Breached password check unavailable: {:?}", e);
+                Ok(None)
+            }
+        }
     }
 
     async fn login(&self, email: &str, password: &str) -> Result<User, DomainError> {
-        let user = self.user_service.find_by_email(&email).await?;
-        user.is_password_match(&password)?;
+        let user = self
+            .user_service
+            .find_by_email_including_deleted(email)
+            .await?;
+        if user.is_deleted() {
+            return Err(DomainError::AuthenticationFailed);
+        }
+        // A locked account is refused even with the right password.
+        if user.is_locked(self.clock.as_ref()) {
+            return Err(DomainError::AuthenticationFailed);
+        }
+        if let Err(e) = self.user_service.check_password(&user, password).await {
+            if matches!(e, DomainError::PasswordNotMatchError)
+                && let Err(record_error) = self.user_service.record_failed_login(&user.id).await
+            {
+                tracing::warn!("Failed to record failed login: {}", record_error);
+            }
+            return Err(e);
+        }
+        let user = match self.user_service.clear_failed_logins(user.clone()).await {
+            Ok(user) => user,
+            Err(e) => {
+                tracing::warn!("Failed to clear failed logins: {}", e);
+                user
+            }
+        };
+        // The login has succeeded; a failed upgrade is retried next time.
+        let user = match self
+            .user_service
+            .upgrade_password_hash(user.clone(), password)
+            .await
+        {
+            Ok(user) => user,
+            Err(e) => {
+                tracing::warn!("Failed to upgrade password hash: {}", e);
+                user
+            }
+        };
+        self.event_publisher.publish(DomainEvent::UserLoggedIn {
+            user_id: user.id.clone(),
+            occurred_at: self.clock.now(),
+        });
         Ok(user)
     }
 
@@ -54,8 +433,944 @@ impl AuthService for DefaultAuthService {
         current_password: &str,
         new_password: &str,
     ) -> Result<User, DomainError> {
-        self.user_service
+        self.ensure_password_not_breached(new_password).await?;
+        let user = self
+            .user_service
             .change_password(user_id, current_password, new_password)
+            .await?;
+        self.revoke_tokens(&user.id, &Self::REVOKED_ON_PASSWORD_CHANGE)
+            .await?;
+        self.event_publisher.publish(DomainEvent::PasswordChanged {
+            user_id: user.id.clone(),
+            occurred_at: self.clock.now(),
+        });
+        Ok(user)
+    }
+
+    async fn update_preferences(
+        &self,
+        user_id: &str,
+        locale: Option<String>,
+        timezone: Option<String>,
+    ) -> Result<User, DomainError> {
+        self.user_service
+            .update_preferences(user_id, locale, timezone)
+            .await
+    }
+
+    async fn request_password_reset(&self, email: &str) -> Result<(), DomainError> {
+        let user = match self.user_service.find_by_email(email).await {
+            Ok(user) => user,
+            Err(DomainError::NotFoundError) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        let now = self.clock.now();
+        self.password_reset_repository
+            .invalidate_for_user(&user.id, now)
+            .await
+            .map_err(DomainError::from_repository)?;
+        let (record, token) = PasswordResetToken::issue(&user.id, self.clock.as_ref());
+        self.password_reset_repository
+            .save(record)
+            .await
+            .map_err(DomainError::from_repository)?;
+
+        self.event_publisher
+            .publish(DomainEvent::PasswordResetRequested {
+                user_id: user.id,
+                email: user.email,
+                token,
+                occurred_at: now,
+            });
+        Ok(())
+    }
+
+    async fn reset_password(&self, token: &str, new_password: &str) -> Result<User, DomainError> {
+        let record = self
+            .password_reset_repository
+            .find_by_token_hash(&hash_token(token))
+            .await
+            .map_err(DomainError::from_repository)?
+            .filter(|record| record.is_usable(self.clock.as_ref()))
+            .ok_or_else(Self::invalid_reset_token)?;
+
+        self.ensure_password_not_breached(new_password).await?;
+        let consumed = self
+            .password_reset_repository
+            .mark_used(&record.id, self.clock.now())
+            .await
+            .map_err(DomainError::from_repository)?;
+        if !consumed {
+            return Err(Self::invalid_reset_token());
+        }
+
+        let user = self
+            .user_service
+            .reset_password(&record.user_id, new_password)
+            .await?;
+        self.revoke_tokens(&user.id, &Self::REVOKED_ON_PASSWORD_CHANGE)
+            .await?;
+        self.event_publisher.publish(DomainEvent::PasswordChanged {
+            user_id: user.id.clone(),
+            occurred_at: self.clock.now(),
+        });
+        Ok(user)
+    }
+
+    async fn verify_email(&self, token: &str) -> Result<User, DomainError> {
+        let record = self
+            .email_verification_repository
+            .find_by_token_hash(&hash_token(token))
+            .await
+            .map_err(DomainError::from_repository)?
+            .filter(|record| record.is_usable(self.clock.as_ref()))
+            .ok_or_else(Self::invalid_verification_token)?;
+
+        let consumed = self
+            .email_verification_repository
+            .mark_used(&record.id, self.clock.now())
+            .await
+            .map_err(DomainError::from_repository)?;
+        if !consumed {
+            return Err(Self::invalid_verification_token());
+        }
+
+        self.user_service.verify_email(&record.user_id).await
+    }
+
+    async fn issue_refresh_token(&self, user_id: &str) -> Result<SecretToken, DomainError> {
+        self.save_refresh_token(user_id, None).await
+    }
+
+    async fn rotate_refresh_token(&self, token: &str) -> Result<(User, SecretToken), DomainError> {
+        let record = self
+            .refresh_token_repository
+            .find_by_token_hash(&hash_token(token))
+            .await
+            .map_err(DomainError::from_repository)?
+            .ok_or(DomainError::InvalidRefreshToken)?;
+
+        if record.is_revoked() {
+            self.revoke_refresh_token_family(&record).await?;
+            return Err(DomainError::InvalidRefreshToken);
+        }
+        if record.is_expired(self.clock.as_ref()) {
+            return Err(DomainError::InvalidRefreshToken);
+        }
+        // Losing this race means another request rotated the same token.
+        let revoked = self
+            .refresh_token_repository
+            .revoke(&record.id, self.clock.now())
+            .await
+            .map_err(DomainError::from_repository)?;
+        if !revoked {
+            self.revoke_refresh_token_family(&record).await?;
+            return Err(DomainError::InvalidRefreshToken);
+        }
+
+        let user = match self.user_service.find_by_id(&record.user_id).await {
+            Ok(user) => user,
+            Err(DomainError::NotFoundError) => return Err(DomainError::InvalidRefreshToken),
+            Err(e) => return Err(e),
+        };
+        let token = self
+            .save_refresh_token(&user.id, Some(&record.family_id))
+            .await?;
+        Ok((user, token))
+    }
+
+    async fn revoke_tokens(&self, user_id: &str, kinds: &[TokenKind]) -> Result<(), DomainError> {
+        let now = self.clock.now();
+        for kind in kinds {
+            let revoked = match kind {
+                TokenKind::PasswordReset => {
+                    self.password_reset_repository
+                        .invalidate_for_user(user_id, now)
+                        .await
+                }
+                TokenKind::EmailVerification => {
+                    self.email_verification_repository
+                        .invalidate_for_user(user_id, now)
+                        .await
+                }
+                TokenKind::Refresh => {
+                    self.refresh_token_repository
+                        .revoke_for_user(user_id, now)
+                        .await
+                }
+                TokenKind::MagicLink => {
+                    self.magic_link_repository
+                        .invalidate_for_user(user_id, now)
+                        .await
+                }
+                TokenKind::EmailChange => {
+                    self.pending_email_change_repository
+                        .invalidate_for_user(user_id, now)
+                        .await
+                }
+            };
+            revoked.map_err(DomainError::from_repository)?;
+        }
+        tracing::info!("Revoked {:?} tokens of user {}", kinds, user_id);
+        self.event_publisher.publish(DomainEvent::TokensRevoked {
+            user_id: user_id.to_string(),
+            kinds: kinds.to_vec(),
+            occurred_at: now,
+        });
+        Ok(())
+    }
+
+    fn oauth_authorization_url(&self, provider: &str, state: &str) -> Result<String, DomainError> {
+        Ok(self.oauth_provider(provider)?.authorization_url(state))
+    }
+
+    async fn login_with_oauth(&self, provider: &str, code: &str) -> Result<User, DomainError> {
+        let info = match self.oauth_provider(provider)?.exchange_code(code).await {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::warn!("OAuth code exchange with {} failed: {:?}", provider, e);
+                return Err(DomainError::AuthenticationFailed);
+            }
+        };
+        let identity = self
+            .oauth_identity_repository
+            .find(provider, &info.subject)
+            .await
+            .map_err(DomainError::from_repository)?;
+        let user = match identity {
+            // The identity outlives a soft-deleted user until it is purged.
+            Some(identity) => match self.user_service.find_by_id(&identity.user_id).await {
+                Ok(user) => user,
+                Err(DomainError::NotFoundError) => return Err(DomainError::AuthenticationFailed),
+                Err(e) => return Err(e),
+            },
+            None => self.link_oauth_identity(provider, &info).await?,
+        };
+        self.event_publisher.publish(DomainEvent::UserLoggedIn {
+            user_id: user.id.clone(),
+            occurred_at: self.clock.now(),
+        });
+        Ok(user)
+    }
+
+    async fn request_magic_link(&self, email: &str) -> Result<(), DomainError> {
+        let user = match self.user_service.find_by_email(email).await {
+            Ok(user) => user,
+            Err(DomainError::NotFoundError) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        let now = self.clock.now();
+        self.magic_link_repository
+            .invalidate_for_user(&user.id, now)
+            .await
+            .map_err(DomainError::from_repository)?;
+        let (record, token) = MagicLinkToken::issue(&user.id, self.clock.as_ref());
+        self.magic_link_repository
+            .save(record)
+            .await
+            .map_err(DomainError::from_repository)?;
+
+        self.event_publisher
+            .publish(DomainEvent::MagicLinkRequested {
+                user_id: user.id,
+                email: user.email,
+                token,
+                occurred_at: now,
+            });
+        Ok(())
+    }
+
+    async fn login_with_magic_link(&self, token: &str) -> Result<User, DomainError> {
+        let record = self
+            .magic_link_repository
+            .find_by_token_hash(&hash_token(token))
+            .await
+            .map_err(DomainError::from_repository)?
+            .filter(|record| record.is_usable(self.clock.as_ref()))
+            .ok_or(DomainError::InvalidMagicLink)?;
+        let consumed = self
+            .magic_link_repository
+            .mark_used(&record.id, self.clock.now())
+            .await
+            .map_err(DomainError::from_repository)?;
+        if !consumed {
+            return Err(DomainError::InvalidMagicLink);
+        }
+
+        let user = match self.user_service.find_by_id(&record.user_id).await {
+            Ok(user) => user,
+            Err(DomainError::NotFoundError) => return Err(DomainError::InvalidMagicLink),
+            Err(e) => return Err(e),
+        };
+        let user = match user.email_verified_at {
+            Some(_) => user,
+            None => self.user_service.verify_email(&user.id).await?,
+        };
+        self.event_publisher.publish(DomainEvent::UserLoggedIn {
+            user_id: user.id.clone(),
+            occurred_at: self.clock.now(),
+        });
+        Ok(user)
+    }
+
+    async fn request_email_change(
+        &self,
+        user_id: &str,
+        current_password: &str,
+        new_email: &str,
+    ) -> Result<(), DomainError> {
+        let user = self
+            .user_service
+            .verify_password(user_id, current_password)
+            .await?;
+        if user.email == new_email.to_lowercase() {
+            return Err(DomainError::validation(
+                "same_email_error",
+                "The new email is the current one",
+            ));
+        }
+        match self.user_service.find_by_email(new_email).await {
+            Ok(_) => return Err(DomainError::unique_conflict("email")),
+            Err(DomainError::NotFoundError) => {}
+            Err(e) => return Err(e),
+        }
+        self.ensure_email_domain_accepts_mail(new_email).await?;
+
+        let now = self.clock.now();
+        self.pending_email_change_repository
+            .invalidate_for_user(&user.id, now)
             .await
+            .map_err(DomainError::from_repository)?;
+        let (record, token) = PendingEmailChange::issue(&user.id, new_email, self.clock.as_ref());
+        let record = self
+            .pending_email_change_repository
+            .save(record)
+            .await
+            .map_err(DomainError::from_repository)?;
+
+        self.event_publisher
+            .publish(DomainEvent::EmailChangeRequested {
+                user_id: user.id,
+                new_email: record.new_email,
+                token,
+                occurred_at: now,
+            });
+        Ok(())
+    }
+
+    async fn confirm_email_change(&self, token: &str) -> Result<User, DomainError> {
+        let record = self
+            .pending_email_change_repository
+            .find_by_token_hash(&hash_token(token))
+            .await
+            .map_err(DomainError::from_repository)?
+            .filter(|record| record.is_usable(self.clock.as_ref()))
+            .ok_or_else(Self::invalid_email_change_token)?;
+        let consumed = self
+            .pending_email_change_repository
+            .mark_used(&record.id, self.clock.now())
+            .await
+            .map_err(DomainError::from_repository)?;
+        if !consumed {
+            return Err(Self::invalid_email_change_token());
+        }
+
+        let previous_email = self.user_service.find_by_id(&record.user_id).await?.email;
+        let user = self
+            .user_service
+            .change_email(&record.user_id, &record.new_email)
+            .await?;
+        // Links mailed to the previous address must not outlive the change.
+        self.revoke_tokens(&user.id, &TokenKind::ALL).await?;
+        self.event_publisher.publish(DomainEvent::EmailChanged {
+            user_id: user.id.clone(),
+            previous_email,
+            email: user.email.clone(),
+            occurred_at: self.clock.now(),
+        });
+        Ok(user)
+    }
+
+    async fn delete_account(
+        &self,
+        user_id: &str,
+        current_password: &str,
+    ) -> Result<(), DomainError> {
+        self.user_service
+            .verify_password(user_id, current_password)
+            .await?;
+        self.user_service.delete_user(user_id).await?;
+        // The soft-deleted row keeps its tokens; none may outlive the account.
+        self.revoke_tokens(user_id, &TokenKind::ALL).await?;
+        tracing::info!("Deleted account of user {}", user_id);
+        self.event_publisher.publish(DomainEvent::AccountDeleted {
+            user_id: user_id.to_string(),
+            occurred_at: self.clock.now(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::user::api::user_service::DefaultUserService;
+    use crate::application::user::password_hashing::PasswordHashingLimiter;
+    use crate::application::user::spi::user_repository::UserRepository;
+    use crate::domain::clock::SystemClock;
+    use crate::domain::common::DateTimeUtc;
+    use crate::domain::lockout::LockoutPolicy;
+    use crate::domain::password::{BcryptHasher, PasswordIdentifierPolicy, PasswordNormalization};
+    use crate::infrastructure::event_bus::BroadcastEventBus;
+    use std::sync::RwLock;
+    use tokio::sync::broadcast::Receiver;
+
+    const EMAIL: &str = "john@example.com";
+    const PASSWORD: &str = "securePassword123!";
+
+    #[derive(Default)]
+    struct InMemoryUserRepository {
+        users: RwLock<HashMap<String, User>>,
+    }
+
+    #[async_trait::async_trait]
+    impl UserRepository for InMemoryUserRepository {
+        async fn find_by_email(&self, email: &str) -> anyhow::Result<Option<User>> {
+            let users = self.users.read().unwrap();
+            Ok(users.values().find(|user| user.email == email).cloned())
+        }
+
+        async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<User>> {
+            Ok(self.users.read().unwrap().get(id).cloned())
+        }
+
+        async fn find_by_email_including_deleted(
+            &self,
+            email: &str,
+        ) -> anyhow::Result<Option<User>> {
+            self.find_by_email(email).await
+        }
+
+        async fn find_by_id_including_deleted(&self, id: &str) -> anyhow::Result<Option<User>> {
+            self.find_by_id(id).await
+        }
+
+        async fn save(&self, user: User) -> anyhow::Result<User> {
+            let mut users = self.users.write().unwrap();
+            users.insert(user.id.clone(), user.clone());
+            Ok(user)
+        }
+
+        async fn update(&self, user: User) -> anyhow::Result<Option<User>> {
+            self.save(user).await.map(Some)
+        }
+
+        async fn replace_password_hash(
+            &self,
+            _id: &str,
+            _current_hash: &str,
+            _new_hash: &str,
+        ) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn increment_failed_logins(&self, id: &str) -> anyhow::Result<Option<i32>> {
+            let mut users = self.users.write().unwrap();
+            Ok(users.get_mut(id).map(|user| {
+                user.failed_login_attempts += 1;
+                user.failed_login_attempts
+            }))
+        }
+
+        async fn lock_until(&self, _id: &str, _until: DateTimeUtc) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn clear_failed_logins(&self, _id: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn soft_delete(&self, id: &str, _at: DateTimeUtc) -> anyhow::Result<()> {
+            self.delete(id).await
+        }
+
+        async fn delete(&self, id: &str) -> anyhow::Result<()> {
+            self.users.write().unwrap().remove(id);
+            Ok(())
+        }
+
+        async fn count(&self) -> anyhow::Result<u64> {
+            let users = self.users.read().unwrap();
+            Ok(users.values().filter(|user| !user.is_deleted()).count() as u64)
+        }
+    }
+
+    struct NoBreaches;
+
+    #[async_trait::async_trait]
+    impl BreachedPasswordChecker for NoBreaches {
+        async fn breach_count(&self, _password: &str) -> anyhow::Result<u64> {
+            Ok(0)
+        }
+    }
+
+    struct NoopTokenRepository;
+
+    #[async_trait::async_trait]
+    impl PasswordResetRepository for NoopTokenRepository {
+        async fn save(&self, token: PasswordResetToken) -> anyhow::Result<PasswordResetToken> {
+            Ok(token)
+        }
+
+        async fn find_by_token_hash(
+            &self,
+            _token_hash: &str,
+        ) -> anyhow::Result<Option<PasswordResetToken>> {
+            Ok(None)
+        }
+
+        async fn mark_used(&self, _id: &str, _used_at: DateTimeUtc) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn invalidate_for_user(
+            &self,
+            _user_id: &str,
+            _at: DateTimeUtc,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EmailVerificationRepository for NoopTokenRepository {
+        async fn save(
+            &self,
+            token: EmailVerificationToken,
+        ) -> anyhow::Result<EmailVerificationToken> {
+            Ok(token)
+        }
+
+        async fn find_by_token_hash(
+            &self,
+            _token_hash: &str,
+        ) -> anyhow::Result<Option<EmailVerificationToken>> {
+            Ok(None)
+        }
+
+        async fn mark_used(&self, _id: &str, _used_at: DateTimeUtc) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn invalidate_for_user(
+            &self,
+            _user_id: &str,
+            _at: DateTimeUtc,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl RefreshTokenRepository for NoopTokenRepository {
+        async fn save(&self, token: RefreshToken) -> anyhow::Result<RefreshToken> {
+            Ok(token)
+        }
+
+        async fn find_by_token_hash(
+            &self,
+            _token_hash: &str,
+        ) -> anyhow::Result<Option<RefreshToken>> {
+            Ok(None)
+        }
+
+        async fn revoke(&self, _id: &str, _revoked_at: DateTimeUtc) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn revoke_family(
+            &self,
+            _family_id: &str,
+            _revoked_at: DateTimeUtc,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn revoke_for_user(
+            &self,
+            _user_id: &str,
+            _revoked_at: DateTimeUtc,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl OAuthIdentityRepository for NoopTokenRepository {
+        async fn find(
+            &self,
+            _provider: &str,
+            _subject: &str,
+        ) -> anyhow::Result<Option<OAuthIdentity>> {
+            Ok(None)
+        }
+
+        async fn save(&self, identity: OAuthIdentity) -> anyhow::Result<OAuthIdentity> {
+            Ok(identity)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MagicLinkRepository for NoopTokenRepository {
+        async fn save(&self, token: MagicLinkToken) -> anyhow::Result<MagicLinkToken> {
+            Ok(token)
+        }
+
+        async fn find_by_token_hash(
+            &self,
+            _token_hash: &str,
+        ) -> anyhow::Result<Option<MagicLinkToken>> {
+            Ok(None)
+        }
+
+        async fn mark_used(&self, _id: &str, _used_at: DateTimeUtc) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn invalidate_for_user(
+            &self,
+            _user_id: &str,
+            _at: DateTimeUtc,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl PendingEmailChangeRepository for NoopTokenRepository {
+        async fn save(&self, change: PendingEmailChange) -> anyhow::Result<PendingEmailChange> {
+            Ok(change)
+        }
+
+        async fn find_by_token_hash(
+            &self,
+            _token_hash: &str,
+        ) -> anyhow::Result<Option<PendingEmailChange>> {
+            Ok(None)
+        }
+
+        async fn mark_used(&self, _id: &str, _used_at: DateTimeUtc) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn invalidate_for_user(
+            &self,
+            _user_id: &str,
+            _at: DateTimeUtc,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryRefreshTokenRepository {
+        tokens: RwLock<HashMap<String, RefreshToken>>,
+    }
+
+    #[async_trait::async_trait]
+    impl RefreshTokenRepository for InMemoryRefreshTokenRepository {
+        async fn save(&self, token: RefreshToken) -> anyhow::Result<RefreshToken> {
+            let mut tokens = self.tokens.write().unwrap();
+            tokens.insert(token.id.clone(), token.clone());
+            Ok(token)
+        }
+
+        async fn find_by_token_hash(
+            &self,
+            token_hash: &str,
+        ) -> anyhow::Result<Option<RefreshToken>> {
+            let tokens = self.tokens.read().unwrap();
+            Ok(tokens
+                .values()
+                .find(|token| token.token_hash == token_hash)
+                .cloned())
+        }
+
+        async fn revoke(&self, id: &str, revoked_at: DateTimeUtc) -> anyhow::Result<bool> {
+            let mut tokens = self.tokens.write().unwrap();
+            Ok(tokens
+                .get_mut(id)
+                .filter(|token| !token.is_revoked())
+                .map(|token| token.revoked_at = Some(revoked_at))
+                .is_some())
+        }
+
+        async fn revoke_family(
+            &self,
+            family_id: &str,
+            revoked_at: DateTimeUtc,
+        ) -> anyhow::Result<()> {
+            let mut tokens = self.tokens.write().unwrap();
+            tokens
+                .values_mut()
+                .filter(|token| token.family_id == family_id && !token.is_revoked())
+                .for_each(|token| token.revoked_at = Some(revoked_at));
+            Ok(())
+        }
+
+        async fn revoke_for_user(
+            &self,
+            user_id: &str,
+            revoked_at: DateTimeUtc,
+        ) -> anyhow::Result<()> {
+            let mut tokens = self.tokens.write().unwrap();
+            tokens
+                .values_mut()
+                .filter(|token| token.user_id == user_id && !token.is_revoked())
+                .for_each(|token| token.revoked_at = Some(revoked_at));
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct InMemoryPasswordResetRepository {
+        tokens: RwLock<HashMap<String, PasswordResetToken>>,
+    }
+
+    #[async_trait::async_trait]
+    impl PasswordResetRepository for InMemoryPasswordResetRepository {
+        async fn save(&self, token: PasswordResetToken) -> anyhow::Result<PasswordResetToken> {
+            let mut tokens = self.tokens.write().unwrap();
+            tokens.insert(token.id.clone(), token.clone());
+            Ok(token)
+        }
+
+        async fn find_by_token_hash(
+            &self,
+            token_hash: &str,
+        ) -> anyhow::Result<Option<PasswordResetToken>> {
+            let tokens = self.tokens.read().unwrap();
+            Ok(tokens
+                .values()
+                .find(|token| token.token_hash == token_hash)
+                .cloned())
+        }
+
+        async fn mark_used(&self, id: &str, used_at: DateTimeUtc) -> anyhow::Result<bool> {
+            let mut tokens = self.tokens.write().unwrap();
+            Ok(tokens
+                .get_mut(id)
+                .filter(|token| token.used_at.is_none())
+                .map(|token| token.used_at = Some(used_at))
+                .is_some())
+        }
+
+        async fn invalidate_for_user(&self, user_id: &str, at: DateTimeUtc) -> anyhow::Result<()> {
+            let mut tokens = self.tokens.write().unwrap();
+            tokens
+                .values_mut()
+                .filter(|token| token.user_id == user_id && token.used_at.is_none())
+                .for_each(|token| token.used_at = Some(at));
+            Ok(())
+        }
+    }
+
+    fn service(event_bus: Arc<BroadcastEventBus>) -> DefaultAuthService {
+        let user_repository = Arc::new(InMemoryUserRepository::default());
+        let user_service = Arc::new(DefaultUserService {
+            user_repository: user_repository.clone(),
+            user_read_repository: user_repository,
+            clock: Arc::new(SystemClock),
+            password_hashing: PasswordHashingLimiter::default(),
+            password_hasher: Arc::new(BcryptHasher { cost: 4 }),
+            password_normalization: PasswordNormalization::default(),
+            password_identifier_policy: PasswordIdentifierPolicy::default(),
+            lockout_policy: LockoutPolicy::disabled(),
+            deletion_grace: chrono::Duration::zero(),
+        });
+        DefaultAuthService {
+            user_service,
+            event_publisher: event_bus,
+            clock: Arc::new(SystemClock),
+            disclose_email_availability: false,
+            breached_password_checker: Arc::new(NoBreaches),
+            breached_password_mode: BreachedPasswordMode::Off,
+            email_domain_checker: None,
+            password_reset_repository: Arc::new(NoopTokenRepository),
+            email_verification: false,
+            email_verification_repository: Arc::new(NoopTokenRepository),
+            refresh_token_repository: Arc::new(NoopTokenRepository),
+            refresh_token_ttl: chrono::Duration::days(30),
+            oauth_providers: HashMap::new(),
+            oauth_identity_repository: Arc::new(NoopTokenRepository),
+            magic_link_repository: Arc::new(NoopTokenRepository),
+            pending_email_change_repository: Arc::new(NoopTokenRepository),
+        }
+    }
+
+    fn next_event(receiver: &mut Receiver<DomainEvent>) -> DomainEvent {
+        receiver.try_recv().expect("an event was published")
+    }
+
+    #[tokio::test]
+    async fn register_publishes_user_registered() {
+        let event_bus = Arc::new(BroadcastEventBus::new());
+        let mut receiver = event_bus.subscribe();
+        let service = service(event_bus);
+
+        let user = service.register(EMAIL, PASSWORD).await.unwrap();
+
+        assert!(matches!(
+            next_event(&mut receiver),
+            DomainEvent::UserRegistered { user_id, email, .. }
+                if user_id == user.id && email == EMAIL
+        ));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn login_publishes_user_logged_in() {
+        let event_bus = Arc::new(BroadcastEventBus::new());
+        let service = service(event_bus.clone());
+        let user = service.register(EMAIL, PASSWORD).await.unwrap();
+        let mut receiver = event_bus.subscribe();
+
+        service.login(EMAIL, PASSWORD).await.unwrap();
+
+        assert!(matches!(
+            next_event(&mut receiver),
+            DomainEvent::UserLoggedIn { user_id, .. } if user_id == user.id
+        ));
+    }
+
+    #[tokio::test]
+    async fn failed_login_publishes_nothing() {
+        let event_bus = Arc::new(BroadcastEventBus::new());
+        let service = service(event_bus.clone());
+        service.register(EMAIL, PASSWORD).await.unwrap();
+        let mut receiver = event_bus.subscribe();
+
+        assert!(service.login(EMAIL, "wrongPassword123!").await.is_err());
+
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn change_password_publishes_password_changed() {
+        let event_bus = Arc::new(BroadcastEventBus::new());
+        let service = service(event_bus.clone());
+        let user = service.register(EMAIL, PASSWORD).await.unwrap();
+        let mut receiver = event_bus.subscribe();
+
+        service
+            .change_password(&user.id, PASSWORD, "newSecurePassword456!")
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            next_event(&mut receiver),
+            DomainEvent::TokensRevoked { user_id, .. } if user_id == user.id
+        ));
+        assert!(matches!(
+            next_event(&mut receiver),
+            DomainEvent::PasswordChanged { user_id, .. } if user_id == user.id
+        ));
+    }
+
+    fn service_with_refresh_tokens(event_bus: Arc<BroadcastEventBus>) -> DefaultAuthService {
+        DefaultAuthService {
+            refresh_token_repository: Arc::new(InMemoryRefreshTokenRepository::default()),
+            ..service(event_bus)
+        }
+    }
+
+    #[tokio::test]
+    async fn rotation_replaces_the_refresh_token() {
+        let service = service_with_refresh_tokens(Arc::new(BroadcastEventBus::new()));
+        let user = service.register(EMAIL, PASSWORD).await.unwrap();
+        let token = service.issue_refresh_token(&user.id).await.unwrap();
+
+        let (rotated_user, rotated) = service.rotate_refresh_token(&token.0).await.unwrap();
+
+        assert_eq!(rotated_user.id, user.id);
+        assert_ne!(rotated, token);
+        assert!(service.rotate_refresh_token(&rotated.0).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn reusing_a_rotated_token_revokes_its_family() {
+        let service = service_with_refresh_tokens(Arc::new(BroadcastEventBus::new()));
+        let user = service.register(EMAIL, PASSWORD).await.unwrap();
+        let token = service.issue_refresh_token(&user.id).await.unwrap();
+        let (_, rotated) = service.rotate_refresh_token(&token.0).await.unwrap();
+
+        let reuse = service.rotate_refresh_token(&token.0).await;
+
+        assert!(matches!(reuse, Err(DomainError::InvalidRefreshToken)));
+        assert!(matches!(
+            service.rotate_refresh_token(&rotated.0).await,
+            Err(DomainError::InvalidRefreshToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn family_revocation_spares_other_families() {
+        let service = service_with_refresh_tokens(Arc::new(BroadcastEventBus::new()));
+        let user = service.register(EMAIL, PASSWORD).await.unwrap();
+        let token = service.issue_refresh_token(&user.id).await.unwrap();
+        let other_device = service.issue_refresh_token(&user.id).await.unwrap();
+        service.rotate_refresh_token(&token.0).await.unwrap();
+
+        assert!(service.rotate_refresh_token(&token.0).await.is_err());
+
+        assert!(service.rotate_refresh_token(&other_device.0).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn change_password_revokes_refresh_tokens() {
+        let service = service_with_refresh_tokens(Arc::new(BroadcastEventBus::new()));
+        let user = service.register(EMAIL, PASSWORD).await.unwrap();
+        let token = service.issue_refresh_token(&user.id).await.unwrap();
+
+        service
+            .change_password(&user.id, PASSWORD, "newSecurePassword456!")
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service.rotate_refresh_token(&token.0).await,
+            Err(DomainError::InvalidRefreshToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn reset_password_revokes_refresh_tokens() {
+        let event_bus = Arc::new(BroadcastEventBus::new());
+        let service = DefaultAuthService {
+            password_reset_repository: Arc::new(InMemoryPasswordResetRepository::default()),
+            ..service_with_refresh_tokens(event_bus.clone())
+        };
+        let user = service.register(EMAIL, PASSWORD).await.unwrap();
+        let token = service.issue_refresh_token(&user.id).await.unwrap();
+        let mut receiver = event_bus.subscribe();
+        service.request_password_reset(EMAIL).await.unwrap();
+        let DomainEvent::PasswordResetRequested {
+            token: reset_token, ..
+        } = next_event(&mut receiver)
+        else {
+            panic!("expected a password reset request");
+        };
+
+        service
+            .reset_password(&reset_token.0, "newSecurePassword456!")
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service.rotate_refresh_token(&token.0).await,
+            Err(DomainError::InvalidRefreshToken)
+        ));
     }
 }
@@ -0,0 +1,21 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#[async_trait::async_trait]
+pub trait BreachedPasswordChecker: Send + Sync + 'static {
+    /// Number of known breaches the password appears in, 0 if none.
+    async fn breach_count(&self, password: &str) -> anyhow::Result<u64>;
+}
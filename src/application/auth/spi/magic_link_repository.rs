@@ -0,0 +1,35 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::common::DateTimeUtc;
+use crate::domain::magic_link::MagicLinkToken;
+
+#[async_trait::async_trait]
+pub trait MagicLinkRepository: Send + Sync + 'static {
+    async fn save(&self, token: MagicLinkToken) -> anyhow::Result<MagicLinkToken>;
+
+    async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<MagicLinkToken>>;
+
+    /// Marks the token used unless it already was. Returns whether this call
+    /// consumed it, so concurrent logins with the same token cannot both win.
+    async fn mark_used(&self, id: &str, used_at: DateTimeUtc) -> anyhow::Result<bool>;
+
+    /// Marks every outstanding token of the user used.
+    async fn invalidate_for_user(&self, user_id: &str, at: DateTimeUtc) -> anyhow::Result<()>;
+}
@@ -0,0 +1,24 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+pub mod breached_password_checker;
+pub mod email_domain_checker;
+pub mod email_verification_repository;
+pub mod magic_link_repository;
+pub mod oauth_identity_repository;
+pub mod oauth_provider;
+pub mod password_reset_repository;
+pub mod pending_email_change_repository;
+pub mod refresh_token_repository;
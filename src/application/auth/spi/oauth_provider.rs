@@ -0,0 +1,30 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::oauth::OAuthUserInfo;
+
+#[async_trait::async_trait]
+pub trait OAuthProvider: Send + Sync + 'static {
+    /// Identifies the provider in routes and stored identities.
+    fn name(&self) -> &'static str;
+
+    /// Where to send the browser to sign in; `state` must come back unchanged
+    /// to the callback.
+    fn authorization_url(&self, state: &str) -> String;
+
+    /// Exchanges the authorization code from the callback for the user's info.
+    async fn exchange_code(&self, code: &str) -> anyhow::Result<OAuthUserInfo>;
+}
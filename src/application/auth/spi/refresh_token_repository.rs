@@ -0,0 +1,33 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::common::DateTimeUtc;
+use crate::domain::refresh_token::RefreshToken;
+
+#[async_trait::async_trait]
+pub trait RefreshTokenRepository: Send + Sync + 'static {
+    async fn save(&self, token: RefreshToken) -> anyhow::Result<RefreshToken>;
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> anyhow::Result<Option<RefreshToken>>;
+
+    /// Revokes the token unless it already was. Returns whether this call
+    /// revoked it, so only one of two concurrent rotations succeeds.
+    async fn revoke(&self, id: &str, revoked_at: DateTimeUtc) -> anyhow::Result<bool>;
+
+    async fn revoke_family(&self, family_id: &str, revoked_at: DateTimeUtc) -> anyhow::Result<()>;
+
+    async fn revoke_for_user(&self, user_id: &str, revoked_at: DateTimeUtc) -> anyhow::Result<()>;
+}
@@ -14,5 +14,7 @@
  * limitations under the License.
  */
 pub mod auth;
+pub mod event;
 pub mod health;
+pub mod session;
 pub mod user;
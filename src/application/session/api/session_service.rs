@@ -0,0 +1,78 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::session::spi::session_repository::SessionRepository;
+use crate::domain::clock::Clock;
+use crate::domain::common::DomainError;
+use crate::domain::session::SessionMetadata;
+use std::sync::Arc;
+
+#[async_trait::async_trait]
+pub trait SessionService: Send + Sync + 'static {
+    async fn record_session(
+        &self,
+        session_id: &str,
+        user_id: &str,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> Result<SessionMetadata, DomainError>;
+
+    async fn list_sessions(&self, user_id: &str) -> Result<Vec<SessionMetadata>, DomainError>;
+
+    async fn remove_session(&self, session_id: &str) -> Result<(), DomainError>;
+}
+
+pub struct DefaultSessionService {
+    pub session_repository: Arc<dyn SessionRepository>,
+    pub clock: Arc<dyn Clock>,
+}
+
+#[async_trait::async_trait]
+impl SessionService for DefaultSessionService {
+    async fn record_session(
+        &self,
+        session_id: &str,
+        user_id: &str,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+    ) -> Result<SessionMetadata, DomainError> {
+        let session = SessionMetadata::new(
+            session_id,
+            user_id,
+            user_agent,
+            ip_address,
+            self.clock.as_ref(),
+        );
+        self.session_repository
+            .save(session)
+            .await
+            .map_err(DomainError::from_repository)
+    }
+
+    async fn list_sessions(&self, user_id: &str) -> Result<Vec<SessionMetadata>, DomainError> {
+        self.session_repository
+            .find_by_user_id(user_id)
+            .await
+            .map_err(DomainError::from_repository)
+    }
+
+    async fn remove_session(&self, session_id: &str) -> Result<(), DomainError> {
+        self.session_repository
+            .delete(session_id)
+            .await
+            .map_err(DomainError::from_repository)
+    }
+}
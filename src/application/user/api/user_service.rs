@@ -13,8 +13,12 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use crate::application::user::password_hashing::PasswordHashingLimiter;
 use crate::application::user::spi::user_repository::UserRepository;
+use crate::domain::clock::Clock;
 use crate::domain::common::DomainError;
+use crate::domain::lockout::LockoutPolicy;
+use crate::domain::password::{PasswordHasher, PasswordIdentifierPolicy, PasswordNormalization};
 use crate::domain::user::User;
 use std::sync::Arc;
 
@@ -26,18 +30,118 @@ pub trait UserService: Send + Sync + 'static {
         password: &str,
     ) -> Result<User, DomainError>;
 
+    /// Creates an account without a password for an email an identity
+    /// provider has verified.
+    async fn create_passwordless_user(&self, email: &str) -> Result<User, DomainError>;
+
     async fn find_by_email(&self, email: &str) -> Result<User, DomainError>;
 
+    /// Whether a user holds `email`. May lag behind writes, so the answer
+    /// must not guard one.
+    async fn is_email_registered(&self, email: &str) -> Result<bool, DomainError>;
+
+    /// Like `find_by_email`, but also finds a soft-deleted user.
+    async fn find_by_email_including_deleted(&self, email: &str) -> Result<User, DomainError>;
+
+    async fn find_by_id(&self, user_id: &str) -> Result<User, DomainError>;
+
+    /// Re-hashes `password`, already verified against `user`, when the stored
+    /// hash is weaker than the active hasher, and persists it.
+    async fn upgrade_password_hash(&self, user: User, password: &str) -> Result<User, DomainError>;
+
+    /// Counts a wrong password against the user, locking it per the lockout
+    /// policy.
+    async fn record_failed_login(&self, user_id: &str) -> Result<(), DomainError>;
+
+    async fn clear_failed_logins(&self, user: User) -> Result<User, DomainError>;
+
+    /// Checks a new password for an account with `email` against the
+    /// password policy, without hashing it.
+    fn check_new_password(&self, email: &str, password: &str) -> Result<(), DomainError>;
+
+    /// Checks `password` against `user`'s hash without reloading the user.
+    async fn check_password(&self, user: &User, password: &str) -> Result<(), DomainError>;
+
+    /// Checks `password` against the user's current one.
+    async fn verify_password(&self, user_id: &str, password: &str) -> Result<User, DomainError>;
+
     async fn change_password(
         &self,
         user_id: &str,
         current_password: &str,
         new_password: &str,
     ) -> Result<User, DomainError>;
+
+    /// Moves the user to `new_email`, which must not belong to another user.
+    async fn change_email(&self, user_id: &str, new_email: &str) -> Result<User, DomainError>;
+
+    async fn reset_password(&self, user_id: &str, new_password: &str)
+    -> Result<User, DomainError>;
+
+    async fn update_preferences(
+        &self,
+        user_id: &str,
+        locale: Option<String>,
+        timezone: Option<String>,
+    ) -> Result<User, DomainError>;
+
+    async fn verify_email(&self, user_id: &str) -> Result<User, DomainError>;
+
+    /// Soft-deletes the user. The email stays reserved for the deletion
+    /// grace period.
+    async fn delete_user(&self, user_id: &str) -> Result<(), DomainError>;
+
+    /// Counts users that are not deleted. May lag behind writes.
+    async fn count_users(&self) -> Result<u64, DomainError>;
 }
 
 pub struct DefaultUserService {
     pub user_repository: Arc<dyn UserRepository>,
+    /// Serves read-only lookups; may be bound to a lagging replica.
+    pub user_read_repository: Arc<dyn UserRepository>,
+    pub clock: Arc<dyn Clock>,
+    pub password_hashing: PasswordHashingLimiter,
+    /// Hashes new passwords; existing hashes verify whatever their scheme.
+    pub password_hasher: Arc<dyn PasswordHasher>,
+    /// Applied before every hash and verification.
+    pub password_normalization: PasswordNormalization,
+    pub password_identifier_policy: PasswordIdentifierPolicy,
+    pub lockout_policy: LockoutPolicy,
+    /// How long a deleted user's email stays reserved.
+    pub deletion_grace: chrono::Duration,
+}
+
+impl DefaultUserService {
+    /// Fails when a user holds `email`. A deleted user whose grace period
+    /// has passed is purged to release it.
+    async fn ensure_email_available(&self, email: &str) -> Result<(), DomainError> {
+        let existing = self
+            .user_repository
+            .find_by_email_including_deleted(email)
+            .await
+            .map_err(DomainError::from_repository)?;
+        match existing {
+            None => Ok(()),
+            Some(user) if user.holds_email(self.deletion_grace, self.clock.as_ref()) => {
+                Err(DomainError::unique_conflict("email"))
+            }
+            Some(user) => self
+                .user_repository
+                .delete(&user.id)
+                .await
+                .map_err(DomainError::from_repository),
+        }
+    }
+
+    /// Persists `user`, failing with `NotFoundError` when it was deleted
+    /// since it was loaded.
+    async fn update_user(&self, user: User) -> Result<User, DomainError> {
+        match self.user_repository.update(user).await {
+            Ok(Some(user)) => Ok(user),
+            Ok(None) => Err(DomainError::NotFoundError),
+            Err(e) => Err(DomainError::from_repository(e)),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -47,38 +151,169 @@ impl UserService for DefaultUserService {
         email: &str,
         password: &str,
     ) -> Result<User, DomainError> {
-        let user = User::create_new_user(email, password)?;
+        let user = {
+            let (email, password, hasher, normalization, identifier_policy, clock) = (
+                email.to_string(),
+                password.to_string(),
+                self.password_hasher.clone(),
+                self.password_normalization,
+                self.password_identifier_policy,
+                self.clock.clone(),
+            );
+            self.password_hashing
+                .run(move || {
+                    User::create_new_user(
+                        &email,
+                        &password,
+                        hasher.as_ref(),
+                        &normalization,
+                        &identifier_policy,
+                        clock.as_ref(),
+                    )
+                })
+                .await?
+        };
 
-        match self.user_repository.find_by_email(email).await {
-            Ok(Some(_)) => {
-                return Err(DomainError::ConflictError(
-                    "user_already_exists_error".to_string(),
-                ));
-            }
-            Ok(None) => {}
-            Err(e) => {
-                tracing::error!("Error checking for existing user: {:?}", e);
-                return Err(DomainError::InternalError);
-            }
-        }
+        self.ensure_email_available(email).await?;
 
+        // The unique constraint still catches registrations racing past the check above.
         let saved_user = self
             .user_repository
             .save(user)
             .await
-            .map_err(|_| DomainError::InternalError)?;
+            .map_err(DomainError::from_repository)?;
         Ok(saved_user)
     }
 
+    async fn create_passwordless_user(&self, email: &str) -> Result<User, DomainError> {
+        self.ensure_email_available(email).await?;
+        let user = User::create_passwordless_user(email, self.clock.as_ref());
+        self.user_repository
+            .save(user)
+            .await
+            .map_err(DomainError::from_repository)
+    }
+
     async fn find_by_email(&self, email: &str) -> Result<User, DomainError> {
         match self.user_repository.find_by_email(email).await {
             Ok(Some(user)) => Ok(user),
             Ok(None) => Err(DomainError::NotFoundError),
-            Err(e) => {
-                tracing::error!("Error finding user by email: {:?}", e);
-                Err(DomainError::InternalError)
-            }
+            Err(e) => Err(DomainError::from_repository(e)),
+        }
+    }
+
+    async fn is_email_registered(&self, email: &str) -> Result<bool, DomainError> {
+        self.user_read_repository
+            .find_by_email(email)
+            .await
+            .map(|user| user.is_some())
+            .map_err(DomainError::from_repository)
+    }
+
+    async fn find_by_email_including_deleted(&self, email: &str) -> Result<User, DomainError> {
+        match self
+            .user_repository
+            .find_by_email_including_deleted(email)
+            .await
+        {
+            Ok(Some(user)) => Ok(user),
+            Ok(None) => Err(DomainError::NotFoundError),
+            Err(e) => Err(DomainError::from_repository(e)),
+        }
+    }
+
+    async fn find_by_id(&self, user_id: &str) -> Result<User, DomainError> {
+        match self.user_repository.find_by_id(user_id).await {
+            Ok(Some(user)) => Ok(user),
+            Ok(None) => Err(DomainError::NotFoundError),
+            Err(e) => Err(DomainError::from_repository(e)),
+        }
+    }
+
+    async fn record_failed_login(&self, user_id: &str) -> Result<(), DomainError> {
+        // Concurrent wrong guesses must each count, so the increment happens
+        // in the database rather than on the loaded user.
+        let failed_attempts = match self.user_repository.increment_failed_logins(user_id).await {
+            Ok(Some(failed_attempts)) => failed_attempts,
+            Ok(None) => return Err(DomainError::NotFoundError),
+            Err(e) => return Err(DomainError::from_repository(e)),
+        };
+        if let Some(duration) = self.lockout_policy.lock_duration(failed_attempts) {
+            self.user_repository
+                .lock_until(user_id, self.clock.now() + duration)
+                .await
+                .map_err(DomainError::from_repository)?;
+        }
+        Ok(())
+    }
+
+    async fn clear_failed_logins(&self, mut user: User) -> Result<User, DomainError> {
+        if !user.clear_failed_logins() {
+            return Ok(user);
+        }
+        self.user_repository
+            .clear_failed_logins(&user.id)
+            .await
+            .map_err(DomainError::from_repository)?;
+        Ok(user)
+    }
+
+    async fn upgrade_password_hash(&self, user: User, password: &str) -> Result<User, DomainError> {
+        let Some(current_hash) = user.password.clone() else {
+            return Ok(user);
+        };
+        if !user.needs_rehash(self.password_hasher.as_ref()) {
+            return Ok(user);
         }
+
+        let upgraded_user = {
+            let (mut user, password, hasher, normalization) = (
+                user.clone(),
+                password.to_string(),
+                self.password_hasher.clone(),
+                self.password_normalization,
+            );
+            self.password_hashing
+                .run(move || {
+                    user.rehash_password(&password, hasher.as_ref(), &normalization)?;
+                    Ok(user)
+                })
+                .await?
+        };
+        let Some(new_hash) = upgraded_user.password.as_deref() else {
+            return Ok(user);
+        };
+        // A password changed while hashing is kept rather than overwritten.
+        let replaced = self
+            .user_repository
+            .replace_password_hash(&user.id, &current_hash, new_hash)
+            .await
+            .map_err(DomainError::from_repository)?;
+        Ok(if replaced { upgraded_user } else { user })
+    }
+
+    fn check_new_password(&self, email: &str, password: &str) -> Result<(), DomainError> {
+        let password = self.password_normalization.apply(password);
+        self.password_identifier_policy.check(&password, email)
+    }
+
+    async fn check_password(&self, user: &User, password: &str) -> Result<(), DomainError> {
+        let (user, password, normalization) =
+            (user.clone(), password.to_string(), self.password_normalization);
+        self.password_hashing
+            .run(move || user.is_password_match(&password, &normalization))
+            .await
+    }
+
+    async fn verify_password(&self, user_id: &str, password: &str) -> Result<User, DomainError> {
+        let user = match self.user_repository.find_by_id(user_id).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return Err(DomainError::NotFoundError),
+            Err(e) => return Err(DomainError::from_repository(e)),
+        };
+
+        self.check_password(&user, password).await?;
+        Ok(user)
     }
 
     async fn change_password(
@@ -90,21 +325,137 @@ impl UserService for DefaultUserService {
         let mut user = match self.user_repository.find_by_id(user_id).await {
             Ok(Some(user)) => user,
             Ok(None) => return Err(DomainError::NotFoundError),
-            Err(e) => {
-                tracing::error!("Error finding user by id: {:?}", e);
-                return Err(DomainError::InternalError);
-            }
+            Err(e) => return Err(DomainError::from_repository(e)),
         };
 
-        user.is_password_match(current_password)?;
-        user.change_password(new_password)?;
+        let user = {
+            let (current_password, new_password) =
+                (current_password.to_string(), new_password.to_string());
+            let (hasher, normalization, identifier_policy, clock) = (
+                self.password_hasher.clone(),
+                self.password_normalization,
+                self.password_identifier_policy,
+                self.clock.clone(),
+            );
+            self.password_hashing
+                .run(move || {
+                    user.change_password(
+                        &current_password,
+                        &new_password,
+                        hasher.as_ref(),
+                        &normalization,
+                        &identifier_policy,
+                        clock.as_ref(),
+                    )?;
+                    Ok(user)
+                })
+                .await?
+        };
 
-        let updated_user = self
-            .user_repository
-            .update(user)
-            .await
-            .map_err(|_| DomainError::InternalError)?;
+        let updated_user = self.update_user(user).await?;
 
         Ok(updated_user)
     }
+
+    async fn change_email(&self, user_id: &str, new_email: &str) -> Result<User, DomainError> {
+        let mut user = match self.user_repository.find_by_id(user_id).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return Err(DomainError::NotFoundError),
+            Err(e) => return Err(DomainError::from_repository(e)),
+        };
+
+        self.ensure_email_available(new_email).await?;
+
+        user.change_email(new_email, self.clock.as_ref());
+
+        // The unique constraint still catches a registration racing past the check above.
+        self.update_user(user).await
+    }
+
+    async fn reset_password(
+        &self,
+        user_id: &str,
+        new_password: &str,
+    ) -> Result<User, DomainError> {
+        let mut user = match self.user_repository.find_by_id(user_id).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return Err(DomainError::NotFoundError),
+            Err(e) => return Err(DomainError::from_repository(e)),
+        };
+
+        let user = {
+            let (new_password, hasher, normalization, identifier_policy, clock) = (
+                new_password.to_string(),
+                self.password_hasher.clone(),
+                self.password_normalization,
+                self.password_identifier_policy,
+                self.clock.clone(),
+            );
+            self.password_hashing
+                .run(move || {
+                    user.reset_password(
+                        &new_password,
+                        hasher.as_ref(),
+                        &normalization,
+                        &identifier_policy,
+                        clock.as_ref(),
+                    )?;
+                    Ok(user)
+                })
+                .await?
+        };
+
+        let mut updated_user = self.update_user(user).await?;
+        // `update` leaves the lockout columns alone, and the loaded count may
+        // be stale, so the reset always lifts the lock in place.
+        self.user_repository
+            .clear_failed_logins(&updated_user.id)
+            .await
+            .map_err(DomainError::from_repository)?;
+        updated_user.clear_failed_logins();
+        Ok(updated_user)
+    }
+
+    async fn update_preferences(
+        &self,
+        user_id: &str,
+        locale: Option<String>,
+        timezone: Option<String>,
+    ) -> Result<User, DomainError> {
+        let mut user = match self.user_repository.find_by_id(user_id).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return Err(DomainError::NotFoundError),
+            Err(e) => return Err(DomainError::from_repository(e)),
+        };
+
+        user.update_preferences(locale, timezone, self.clock.as_ref());
+
+        self.update_user(user).await
+    }
+
+    async fn verify_email(&self, user_id: &str) -> Result<User, DomainError> {
+        let mut user = match self.user_repository.find_by_id(user_id).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return Err(DomainError::NotFoundError),
+            Err(e) => return Err(DomainError::from_repository(e)),
+        };
+
+        user.verify_email(self.clock.as_ref());
+
+        self.update_user(user).await
+    }
+
+    async fn delete_user(&self, user_id: &str) -> Result<(), DomainError> {
+        self.user_repository
+            .soft_delete(user_id, self.clock.now())
+            .await
+            .map_err(DomainError::from_repository)
+    }
+
+    async fn count_users(&self) -> Result<u64, DomainError> {
+        self.user_read_repository
+            .count()
+            .await
+            .map_err(DomainError::from_repository)
+    }
 }
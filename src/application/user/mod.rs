@@ -15,3 +15,4 @@
  */
 pub mod api;
 pub mod spi;
+pub mod password_hashing;
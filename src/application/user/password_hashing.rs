@@ -0,0 +1,70 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::common::DomainError;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// Bounds how many password hash/verify operations run at once and moves
+/// them off the async workers. Callers waiting for a permit are cancelled
+/// along with their request when it times out.
+#[derive(Clone, Debug)]
+pub struct PasswordHashingLimiter {
+    semaphore: Arc<Semaphore>,
+}
+
+impl PasswordHashingLimiter {
+    pub fn new(max_concurrent: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent.max(1))),
+        }
+    }
+
+    pub fn default_concurrency() -> usize {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    }
+
+    pub async fn run<T, F>(&self, f: F) -> Result<T, DomainError>
+    where
+        F: FnOnce() -> Result<T, DomainError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let permit = self
+            .semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(|e| DomainError::InternalError(e.to_string()))?;
+
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            f()
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("Password hashing task failed: {:?}", e);
+            DomainError::InternalError(e.to_string())
+        })?
+    }
+}
+
+impl Default for PasswordHashingLimiter {
+    fn default() -> Self {
+        Self::new(Self::default_concurrency())
+    }
+}
@@ -14,15 +14,55 @@
  * limitations under the License.
  */
 
+use crate::domain::common::DateTimeUtc;
 use crate::domain::user::User;
 
 #[async_trait::async_trait]
 pub trait UserRepository: Send + Sync + 'static {
+    // An implementation may be bound to a read replica. Lookups whose result
+    // feeds a write must use one bound to the primary, or a lagging replica
+    // would lose writes.
     async fn find_by_email(&self, email: &str) -> anyhow::Result<Option<User>>;
 
     async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<User>>;
 
+    /// Like `find_by_email`, but also returns a soft-deleted user, for
+    /// administration and for checking whether a deleted email is reserved.
+    async fn find_by_email_including_deleted(&self, email: &str) -> anyhow::Result<Option<User>>;
+
+    async fn find_by_id_including_deleted(&self, id: &str) -> anyhow::Result<Option<User>>;
+
     async fn save(&self, user: User) -> anyhow::Result<User>;
 
-    async fn update(&self, user: User) -> anyhow::Result<User>;
+    /// Writes the user's fields except the failed-login count, lock and
+    /// deletion, which only the dedicated methods below change. Returns
+    /// `None` when the user is gone or soft-deleted.
+    async fn update(&self, user: User) -> anyhow::Result<Option<User>>;
+
+    /// Stores `new_hash` only while the password is still `current_hash`, so
+    /// a password changed in the meantime is kept. Returns whether it did.
+    async fn replace_password_hash(
+        &self,
+        id: &str,
+        current_hash: &str,
+        new_hash: &str,
+    ) -> anyhow::Result<bool>;
+
+    /// Counts one more failed login in place and returns the new count, or
+    /// `None` when there is no such user.
+    async fn increment_failed_logins(&self, id: &str) -> anyhow::Result<Option<i32>>;
+
+    async fn lock_until(&self, id: &str, until: DateTimeUtc) -> anyhow::Result<()>;
+
+    /// Resets the failed-login count and lifts any lock.
+    async fn clear_failed_logins(&self, id: &str) -> anyhow::Result<()>;
+
+    /// Marks the user deleted; the finders above then skip it.
+    async fn soft_delete(&self, id: &str, at: DateTimeUtc) -> anyhow::Result<()>;
+
+    /// Removes the user; rows referencing it are removed by cascade.
+    async fn delete(&self, id: &str) -> anyhow::Result<()>;
+
+    /// Counts users that are not soft-deleted.
+    async fn count(&self) -> anyhow::Result<u64>;
 }
@@ -0,0 +1,59 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::common::DateTimeUtc;
+use std::sync::RwLock;
+
+pub trait Clock: Send + Sync + 'static {
+    fn now(&self) -> DateTimeUtc;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTimeUtc {
+        DateTimeUtc::from(chrono::Utc::now())
+    }
+}
+
+#[derive(Debug)]
+pub struct FixedClock {
+    now: RwLock<DateTimeUtc>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTimeUtc) -> FixedClock {
+        FixedClock {
+            now: RwLock::new(now),
+        }
+    }
+
+    pub fn set(&self, now: DateTimeUtc) {
+        *self.now.write().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    pub fn advance(&self, duration: chrono::Duration) {
+        let mut now = self.now.write().unwrap_or_else(|e| e.into_inner());
+        *now += duration;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTimeUtc {
+        *self.now.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
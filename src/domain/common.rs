@@ -18,12 +18,16 @@ pub type DateTimeUtc = chrono::DateTime<chrono::FixedOffset>;
 
 #[derive(thiserror::Error, Debug)]
 pub enum DomainError {
+    /// Carries the underlying cause for logs and non-production responses;
+    /// clients only ever see the code.
     #[error("internal_error")]
-    InternalError,
+    InternalError(String),
     #[error("{0}")]
     ConflictError(String),
     #[error("not_found_error")]
     NotFoundError,
+    #[error("{code}")]
+    ValidationError { code: String, message: String },
     // User
     #[error("password_not_match_error")]
     PasswordNotMatchError,
@@ -33,4 +37,56 @@ pub enum DomainError {
     AuthenticationFailed,
     #[error("invalid_credentials")]
     InvalidCredentials,
+    #[error("invalid_refresh_token_error")]
+    InvalidRefreshToken,
+    #[error("invalid_magic_link_error")]
+    InvalidMagicLink,
+    #[error("service_unavailable_error")]
+    ServiceUnavailable,
+}
+
+/// Raised by repositories when a write hits a unique constraint. `field` names
+/// the domain field, not the database constraint.
+#[derive(thiserror::Error, Debug)]
+#[error("unique constraint violated on {field}")]
+pub struct UniqueViolation {
+    pub field: &'static str,
+}
+
+/// Raised by repositories when the backing store cannot be reached, a
+/// temporary condition callers may retry.
+#[derive(thiserror::Error, Debug)]
+#[error("store unavailable")]
+pub struct StoreUnavailable;
+
+impl DomainError {
+    pub fn unique_conflict(field: &str) -> Self {
+        // Email conflicts keep the code clients already rely on.
+        let code = match field {
+            "email" => "user_already_exists_error".to_string(),
+            field => format!("{}_already_exists_error", field),
+        };
+        DomainError::ConflictError(code)
+    }
+
+    /// Maps a repository failure: unique violations become field-specific
+    /// conflicts, an unreachable store becomes retryable, the rest is internal.
+    pub fn from_repository(error: anyhow::Error) -> Self {
+        if let Some(violation) = error.downcast_ref::<UniqueViolation>() {
+            return Self::unique_conflict(violation.field);
+        }
+        if error.downcast_ref::<StoreUnavailable>().is_some() {
+            tracing::warn!("Repository unavailable: {:#}", error);
+            return DomainError::ServiceUnavailable;
+        }
+        tracing::error!("Repository error: {:?}", error);
+        DomainError::InternalError(format!("{:#}", error))
+    }
+
+    pub fn validation(code: &str, message: &str) -> Self {
+        DomainError::ValidationError {
+            code: code.to_string(),
+            message: message.to_string(),
+        }
+    }
 }
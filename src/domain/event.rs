@@ -0,0 +1,76 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::common::DateTimeUtc;
+use crate::domain::secret_token::{SecretToken, TokenKind};
+use serde::Serialize;
+
+#[derive(Clone, Serialize, Debug, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    UserRegistered {
+        user_id: String,
+        email: String,
+        occurred_at: DateTimeUtc,
+    },
+    UserLoggedIn {
+        user_id: String,
+        occurred_at: DateTimeUtc,
+    },
+    PasswordChanged {
+        user_id: String,
+        occurred_at: DateTimeUtc,
+    },
+    EmailVerificationRequested {
+        user_id: String,
+        email: String,
+        token: SecretToken,
+        occurred_at: DateTimeUtc,
+    },
+    PasswordResetRequested {
+        user_id: String,
+        email: String,
+        token: SecretToken,
+        occurred_at: DateTimeUtc,
+    },
+    MagicLinkRequested {
+        user_id: String,
+        email: String,
+        token: SecretToken,
+        occurred_at: DateTimeUtc,
+    },
+    EmailChangeRequested {
+        user_id: String,
+        new_email: String,
+        token: SecretToken,
+        occurred_at: DateTimeUtc,
+    },
+    EmailChanged {
+        user_id: String,
+        previous_email: String,
+        email: String,
+        occurred_at: DateTimeUtc,
+    },
+    AccountDeleted {
+        user_id: String,
+        occurred_at: DateTimeUtc,
+    },
+    TokensRevoked {
+        user_id: String,
+        kinds: Vec<TokenKind>,
+        occurred_at: DateTimeUtc,
+    },
+}
@@ -18,4 +18,6 @@ use serde::Serialize;
 #[derive(Serialize, Debug, Default)]
 pub struct Health {
     pub healthy: bool,
+    pub degraded: bool,
+    pub session_store_latency_ms: Option<u64>,
 }
@@ -0,0 +1,97 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use chrono::Duration;
+
+/// Locks an account after `threshold` consecutive failed logins, for `base`
+/// at first and doubling with every further failure, up to `max`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockoutPolicy {
+    /// 0 disables lockout.
+    pub threshold: u32,
+    pub base: Duration,
+    pub max: Duration,
+}
+
+impl LockoutPolicy {
+    pub fn disabled() -> Self {
+        Self {
+            threshold: 0,
+            base: Duration::zero(),
+            max: Duration::zero(),
+        }
+    }
+
+    pub fn lock_duration(&self, failed_attempts: i32) -> Option<Duration> {
+        let failed_attempts = u32::try_from(failed_attempts).ok()?;
+        if self.threshold == 0 || failed_attempts < self.threshold {
+            return None;
+        }
+        let doublings = failed_attempts - self.threshold;
+        let duration = 2_i32
+            .checked_pow(doublings)
+            .and_then(|factor| self.base.checked_mul(factor))
+            .unwrap_or(self.max);
+        Some(duration.min(self.max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> LockoutPolicy {
+        LockoutPolicy {
+            threshold: 3,
+            base: Duration::seconds(60),
+            max: Duration::seconds(600),
+        }
+    }
+
+    #[test]
+    fn does_not_lock_below_threshold() {
+        assert_eq!(policy().lock_duration(0), None);
+        assert_eq!(policy().lock_duration(2), None);
+    }
+
+    #[test]
+    fn locks_for_base_duration_at_threshold() {
+        assert_eq!(policy().lock_duration(3), Some(Duration::seconds(60)));
+    }
+
+    #[test]
+    fn doubles_with_each_further_failure() {
+        assert_eq!(policy().lock_duration(4), Some(Duration::seconds(120)));
+        assert_eq!(policy().lock_duration(5), Some(Duration::seconds(240)));
+        assert_eq!(policy().lock_duration(6), Some(Duration::seconds(480)));
+    }
+
+    #[test]
+    fn caps_at_max_duration() {
+        assert_eq!(policy().lock_duration(7), Some(Duration::seconds(600)));
+        assert_eq!(policy().lock_duration(i32::MAX), Some(Duration::seconds(600)));
+    }
+
+    #[test]
+    fn zero_threshold_disables_lockout() {
+        assert_eq!(LockoutPolicy::disabled().lock_duration(100), None);
+    }
+
+    #[test]
+    fn negative_attempts_do_not_lock() {
+        assert_eq!(policy().lock_duration(-1), None);
+    }
+}
@@ -0,0 +1,52 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::clock::Clock;
+use crate::domain::common::DateTimeUtc;
+use crate::domain::secret_token::SecretToken;
+
+pub const MAGIC_LINK_TOKEN_TTL: chrono::Duration = chrono::Duration::minutes(10);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MagicLinkToken {
+    pub id: String,
+    pub user_id: String,
+    pub token_hash: String,
+    pub expires_at: DateTimeUtc,
+    pub used_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+impl MagicLinkToken {
+    /// Returns the stored record and the plaintext token to hand to the user.
+    pub fn issue(user_id: &str, clock: &dyn Clock) -> (MagicLinkToken, SecretToken) {
+        let token = SecretToken::generate();
+        let now = clock.now();
+        let record = MagicLinkToken {
+            id: uuid::Uuid::now_v7().to_string(),
+            user_id: user_id.to_string(),
+            token_hash: token.hash(),
+            expires_at: now + MAGIC_LINK_TOKEN_TTL,
+            used_at: None,
+            created_at: now,
+        };
+        (record, token)
+    }
+
+    pub fn is_usable(&self, clock: &dyn Clock) -> bool {
+        self.used_at.is_none() && clock.now() < self.expires_at
+    }
+}
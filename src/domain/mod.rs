@@ -13,6 +13,18 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+pub mod clock;
 pub mod common;
+pub mod email_change;
+pub mod email_verification;
+pub mod event;
 pub mod health;
+pub mod lockout;
+pub mod magic_link;
+pub mod oauth;
+pub mod password;
+pub mod password_reset;
+pub mod refresh_token;
+pub mod secret_token;
+pub mod session;
 pub mod user;
@@ -0,0 +1,49 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::clock::Clock;
+use crate::domain::common::DateTimeUtc;
+
+/// Links an account at an external identity provider to a local user.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OAuthIdentity {
+    pub id: String,
+    pub user_id: String,
+    pub provider: String,
+    /// The provider's stable user id, unlike the email which may change.
+    pub subject: String,
+    pub created_at: DateTimeUtc,
+}
+
+impl OAuthIdentity {
+    pub fn link(user_id: &str, provider: &str, subject: &str, clock: &dyn Clock) -> Self {
+        Self {
+            id: uuid::Uuid::now_v7().to_string(),
+            user_id: user_id.to_string(),
+            provider: provider.to_string(),
+            subject: subject.to_string(),
+            created_at: clock.now(),
+        }
+    }
+}
+
+/// What a provider vouches for once the user has signed in there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OAuthUserInfo {
+    pub subject: String,
+    pub email: String,
+    pub email_verified: bool,
+}
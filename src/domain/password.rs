@@ -0,0 +1,301 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::common::DomainError;
+use argon2::password_hash::{PasswordHash, SaltString};
+use argon2::{Algorithm, Argon2, Params, PasswordHasher as _, PasswordVerifier as _, Version};
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
+
+/// How a password is canonicalized before it is hashed or verified. Both
+/// steps always apply the same policy, and the default leaves passwords
+/// untouched so existing hashes keep verifying. Changing it after users have
+/// registered breaks verification for passwords whose normalized form
+/// differs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PasswordNormalization {
+    /// NFKC, so composed and decomposed forms of the same text match.
+    pub unicode: bool,
+    /// Strip leading and trailing whitespace.
+    pub trim: bool,
+}
+
+impl PasswordNormalization {
+    pub fn apply<'a>(&self, password: &'a str) -> Cow<'a, str> {
+        let password = if self.trim { password.trim() } else { password };
+        if self.unicode {
+            Cow::Owned(password.nfkc().collect())
+        } else {
+            Cow::Borrowed(password)
+        }
+    }
+}
+
+/// Rejects passwords built from the account's email address.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PasswordIdentifierPolicy {
+    pub enabled: bool,
+    /// A password equal to the local-part is always rejected; one merely
+    /// containing it only when the local-part is at least this long, so
+    /// short local-parts like "jo" do not rule out ordinary words.
+    pub min_match_length: usize,
+}
+
+impl PasswordIdentifierPolicy {
+    /// `password` must already be normalized.
+    pub fn check(&self, password: &str, email: &str) -> Result<(), DomainError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let local_part = email
+            .rsplit_once('@')
+            .map_or(email, |(local_part, _)| local_part)
+            .to_lowercase();
+        if local_part.is_empty() {
+            return Ok(());
+        }
+
+        let password = password.to_lowercase();
+        if password == local_part
+            || (local_part.chars().count() >= self.min_match_length
+                && password.contains(&local_part))
+        {
+            return Err(DomainError::validation(
+                "password_contains_email_error",
+                "The password must not contain the email address",
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub trait PasswordHasher: Send + Sync + 'static {
+    fn hash(&self, password: &str) -> Result<String, DomainError>;
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, DomainError>;
+    /// Whether `hash` comes from another scheme or weaker parameters than
+    /// this hasher would use today.
+    fn needs_rehash(&self, hash: &str) -> bool;
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct BcryptHasher {
+    pub cost: u32,
+}
+
+impl Default for BcryptHasher {
+    fn default() -> Self {
+        Self {
+            cost: bcrypt::DEFAULT_COST,
+        }
+    }
+}
+
+impl PasswordHasher for BcryptHasher {
+    fn hash(&self, password: &str) -> Result<String, DomainError> {
+        bcrypt::hash(password, self.cost).map_err(|e| DomainError::InternalError(e.to_string()))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, DomainError> {
+        bcrypt::verify(password, hash).map_err(|e| DomainError::InternalError(e.to_string()))
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        // "$2b$12$...": the cost is the second field.
+        match hash.split('$').nth(2).map(str::parse::<u32>) {
+            Some(Ok(cost)) if hash.starts_with("$2") => cost < self.cost,
+            _ => true,
+        }
+    }
+}
+
+/// Argon2id. The default parameters are the OWASP baseline (19 MiB, two
+/// passes, one lane); verification reads the parameters from the hash.
+#[derive(Clone, Debug, Default)]
+pub struct Argon2Hasher {
+    pub params: Params,
+}
+
+impl PasswordHasher for Argon2Hasher {
+    fn hash(&self, password: &str) -> Result<String, DomainError> {
+        let salt = SaltString::encode_b64(&rand::random::<[u8; 16]>())
+            .map_err(|e| DomainError::InternalError(e.to_string()))?;
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, self.params.clone())
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| DomainError::InternalError(e.to_string()))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, DomainError> {
+        let hash =
+            PasswordHash::new(hash).map_err(|e| DomainError::InternalError(e.to_string()))?;
+        match Argon2::default().verify_password(password.as_bytes(), &hash) {
+            Ok(()) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(DomainError::InternalError(e.to_string())),
+        }
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        let Ok(hash) = PasswordHash::new(hash) else {
+            return true;
+        };
+        if hash.algorithm != Algorithm::Argon2id.ident() {
+            return true;
+        }
+        match Params::try_from(&hash) {
+            Ok(params) => {
+                params.m_cost() < self.params.m_cost()
+                    || params.t_cost() < self.params.t_cost()
+                    || params.p_cost() < self.params.p_cost()
+            }
+            Err(_) => true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasswordHashAlgorithm {
+    Bcrypt,
+    Argon2,
+}
+
+impl PasswordHashAlgorithm {
+    pub fn hasher(self) -> Arc<dyn PasswordHasher> {
+        match self {
+            Self::Bcrypt => Arc::new(BcryptHasher::default()),
+            Self::Argon2 => Arc::new(Argon2Hasher::default()),
+        }
+    }
+}
+
+impl FromStr for PasswordHashAlgorithm {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "bcrypt" => Ok(Self::Bcrypt),
+            "argon2" | "argon2id" => Ok(Self::Argon2),
+            _ => Err(format!("expected bcrypt or argon2, got {}", value)),
+        }
+    }
+}
+
+/// Verifies against whichever scheme produced `hash`, so bcrypt hashes keep
+/// working after the active hasher switches to Argon2.
+pub fn verify_password(password: &str, hash: &str) -> Result<bool, DomainError> {
+    if hash.starts_with("$argon2") {
+        Argon2Hasher::default().verify(password, hash)
+    } else {
+        BcryptHasher::default().verify(password, hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "é" as one code point (NFC) and as "e" plus a combining acute (NFD).
+    const COMPOSED: &str = "caf\u{e9} secret";
+    const DECOMPOSED: &str = "cafe\u{301} secret";
+
+    #[test]
+    fn unicode_normalization_makes_nfc_and_nfd_equal() {
+        let normalization = PasswordNormalization {
+            unicode: true,
+            trim: false,
+        };
+
+        assert_ne!(COMPOSED, DECOMPOSED);
+        assert_eq!(normalization.apply(COMPOSED), normalization.apply(DECOMPOSED));
+    }
+
+    #[test]
+    fn default_normalization_keeps_nfc_and_nfd_distinct() {
+        let normalization = PasswordNormalization::default();
+
+        assert_eq!(normalization.apply(DECOMPOSED), DECOMPOSED);
+        assert_ne!(normalization.apply(COMPOSED), normalization.apply(DECOMPOSED));
+    }
+
+    #[test]
+    fn trim_strips_surrounding_whitespace_only() {
+        let normalization = PasswordNormalization {
+            unicode: false,
+            trim: true,
+        };
+
+        assert_eq!(normalization.apply("  pass word \t"), "pass word");
+    }
+
+    fn identifier_policy() -> PasswordIdentifierPolicy {
+        PasswordIdentifierPolicy {
+            enabled: true,
+            min_match_length: 4,
+        }
+    }
+
+    fn is_contains_email_error(result: Result<(), DomainError>) -> bool {
+        matches!(
+            result,
+            Err(DomainError::ValidationError { ref code, .. })
+                if code == "password_contains_email_error"
+        )
+    }
+
+    #[test]
+    fn rejects_password_containing_local_part() {
+        let result = identifier_policy().check("xjohnsmith2024!", "johnsmith@example.com");
+
+        assert!(is_contains_email_error(result));
+    }
+
+    #[test]
+    fn rejects_password_equal_to_local_part() {
+        let result = identifier_policy().check("jo", "jo@example.com");
+
+        assert!(is_contains_email_error(result));
+    }
+
+    #[test]
+    fn matches_local_part_case_insensitively() {
+        let result = identifier_policy().check("MyJohnSmith!Pass", "JOHNSMITH@example.com");
+
+        assert!(is_contains_email_error(result));
+    }
+
+    #[test]
+    fn ignores_short_local_part_inside_longer_password() {
+        let result = identifier_policy().check("journey-to-the-west", "jo@example.com");
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn accepts_password_without_local_part() {
+        let result = identifier_policy().check("correct horse battery", "johnsmith@example.com");
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn disabled_policy_accepts_local_part() {
+        let policy = PasswordIdentifierPolicy::default();
+
+        assert!(policy.check("johnsmith", "johnsmith@example.com").is_ok());
+    }
+}
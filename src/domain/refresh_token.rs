@@ -0,0 +1,65 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::clock::Clock;
+use crate::domain::common::DateTimeUtc;
+use crate::domain::secret_token::SecretToken;
+
+/// Every rotation replaces the token with a new one in the same family, so
+/// presenting a rotated token again reveals that a copy is in other hands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RefreshToken {
+    pub id: String,
+    pub user_id: String,
+    pub family_id: String,
+    pub token_hash: String,
+    pub expires_at: DateTimeUtc,
+    pub revoked_at: Option<DateTimeUtc>,
+    pub created_at: DateTimeUtc,
+}
+
+impl RefreshToken {
+    /// Starts a new family on login, or continues `family_id` on rotation.
+    /// Returns the stored record and the plaintext token to hand to the user.
+    pub fn issue(
+        user_id: &str,
+        family_id: Option<&str>,
+        ttl: chrono::Duration,
+        clock: &dyn Clock,
+    ) -> (RefreshToken, SecretToken) {
+        let token = SecretToken::generate();
+        let now = clock.now();
+        let id = uuid::Uuid::now_v7().to_string();
+        let record = RefreshToken {
+            family_id: family_id.map_or_else(|| id.clone(), str::to_string),
+            id,
+            user_id: user_id.to_string(),
+            token_hash: token.hash(),
+            expires_at: now + ttl,
+            revoked_at: None,
+            created_at: now,
+        };
+        (record, token)
+    }
+
+    pub fn is_revoked(&self) -> bool {
+        self.revoked_at.is_some()
+    }
+
+    pub fn is_expired(&self, clock: &dyn Clock) -> bool {
+        clock.now() >= self.expires_at
+    }
+}
@@ -0,0 +1,49 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::clock::Clock;
+use crate::domain::common::DateTimeUtc;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct SessionMetadata {
+    pub id: String,
+    pub user_id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTimeUtc,
+    pub last_seen_at: DateTimeUtc,
+}
+
+impl SessionMetadata {
+    pub fn new(
+        id: &str,
+        user_id: &str,
+        user_agent: Option<String>,
+        ip_address: Option<String>,
+        clock: &dyn Clock,
+    ) -> SessionMetadata {
+        let now = clock.now();
+        SessionMetadata {
+            id: id.to_string(),
+            user_id: user_id.to_string(),
+            user_agent,
+            ip_address,
+            created_at: now,
+            last_seen_at: now,
+        }
+    }
+}
@@ -13,59 +13,242 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use crate::domain::clock::Clock;
 use crate::domain::common::{DateTimeUtc, DomainError};
+use crate::domain::password::{
+    verify_password, PasswordHasher, PasswordIdentifierPolicy, PasswordNormalization,
+};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct User {
     pub id: String,
     pub email: String,
-    pub password: String,
+    /// `None` for accounts created through an external identity provider.
+    pub password: Option<String>,
     pub created_at: DateTimeUtc,
     pub updated_at: DateTimeUtc,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub email_verified_at: Option<DateTimeUtc>,
+    pub failed_login_attempts: i32,
+    pub locked_until: Option<DateTimeUtc>,
+    pub deleted_at: Option<DateTimeUtc>,
+}
+
+// The password hash is redacted so debug-logging a user never leaks it.
+impl fmt::Debug for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("User")
+            .field("id", &self.id)
+            .field("email", &self.email)
+            .field("password", &"[REDACTED]")
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("locale", &self.locale)
+            .field("timezone", &self.timezone)
+            .field("email_verified_at", &self.email_verified_at)
+            .field("failed_login_attempts", &self.failed_login_attempts)
+            .field("locked_until", &self.locked_until)
+            .field("deleted_at", &self.deleted_at)
+            .finish()
+    }
 }
 
 impl User {
-    pub fn create_new_user(email: &str, password: &str) -> Result<User, DomainError> {
-        let hash_password = Self::hash_password(password)?;
+    pub fn create_new_user(
+        email: &str,
+        password: &str,
+        hasher: &dyn PasswordHasher,
+        normalization: &PasswordNormalization,
+        identifier_policy: &PasswordIdentifierPolicy,
+        clock: &dyn Clock,
+    ) -> Result<User, DomainError> {
+        let password = normalization.apply(password);
+        identifier_policy.check(&password, email)?;
+        let hash_password = hasher.hash(&password)?;
+        let now = clock.now();
         let user = User {
             id: uuid::Uuid::now_v7().to_string(),
             email: email.to_lowercase(),
-            password: hash_password,
-            created_at: DateTimeUtc::from(chrono::Utc::now()),
-            updated_at: DateTimeUtc::from(chrono::Utc::now()),
+            password: Some(hash_password),
+            created_at: now,
+            updated_at: now,
+            locale: None,
+            timezone: None,
+            email_verified_at: None,
+            failed_login_attempts: 0,
+            locked_until: None,
+            deleted_at: None,
         };
         Ok(user)
     }
 
-    pub fn hash_password(password: &str) -> Result<String, DomainError> {
-        bcrypt::hash(password, bcrypt::DEFAULT_COST).map_err(|_| DomainError::InternalError)
+    /// Creates an account without a password for an email an identity
+    /// provider has already verified.
+    pub fn create_passwordless_user(email: &str, clock: &dyn Clock) -> User {
+        let now = clock.now();
+        User {
+            id: uuid::Uuid::now_v7().to_string(),
+            email: email.to_lowercase(),
+            password: None,
+            created_at: now,
+            updated_at: now,
+            locale: None,
+            timezone: None,
+            email_verified_at: Some(now),
+            failed_login_attempts: 0,
+            locked_until: None,
+            deleted_at: None,
+        }
     }
 
-    pub fn is_password_match(&self, password: &str) -> Result<(), DomainError> {
-        match bcrypt::verify(password, &self.password) {
-            Ok(true) => Ok(()),
-            Ok(false) => Err(DomainError::PasswordNotMatchError),
-            Err(_) => Err(DomainError::InternalError),
+    pub fn hash_password(
+        password: &str,
+        hasher: &dyn PasswordHasher,
+        normalization: &PasswordNormalization,
+    ) -> Result<String, DomainError> {
+        hasher.hash(&normalization.apply(password))
+    }
+
+    pub fn is_password_match(
+        &self,
+        password: &str,
+        normalization: &PasswordNormalization,
+    ) -> Result<(), DomainError> {
+        let Some(hash) = &self.password else {
+            return Err(DomainError::PasswordNotMatchError);
+        };
+        match verify_password(&normalization.apply(password), hash)? {
+            true => Ok(()),
+            false => Err(DomainError::PasswordNotMatchError),
         }
     }
 
-    pub fn change_password(&mut self, new_password: &str) -> Result<(), DomainError> {
-        if self.is_password_match(new_password).is_ok() {
+    pub fn needs_rehash(&self, hasher: &dyn PasswordHasher) -> bool {
+        self.password
+            .as_deref()
+            .is_some_and(|hash| hasher.needs_rehash(hash))
+    }
+
+    /// Re-hashes an already verified password with `hasher`. The password
+    /// itself is unchanged, so `updated_at` is left alone.
+    pub fn rehash_password(
+        &mut self,
+        password: &str,
+        hasher: &dyn PasswordHasher,
+        normalization: &PasswordNormalization,
+    ) -> Result<(), DomainError> {
+        self.password = Some(Self::hash_password(password, hasher, normalization)?);
+        Ok(())
+    }
+
+    /// Verifies `current_password` and replaces it. With the current password
+    /// proven, reuse is a plain comparison, so only one hash verification runs.
+    pub fn change_password(
+        &mut self,
+        current_password: &str,
+        new_password: &str,
+        hasher: &dyn PasswordHasher,
+        normalization: &PasswordNormalization,
+        identifier_policy: &PasswordIdentifierPolicy,
+        clock: &dyn Clock,
+    ) -> Result<(), DomainError> {
+        self.is_password_match(current_password, normalization)?;
+        let new_password = normalization.apply(new_password);
+        if normalization.apply(current_password) == new_password {
             return Err(DomainError::SamePasswordError);
         }
+        identifier_policy.check(&new_password, &self.email)?;
+
+        let hashed_password = hasher.hash(&new_password)?;
+        self.password = Some(hashed_password);
+        self.updated_at = clock.now();
+        Ok(())
+    }
 
-        let hashed_password = Self::hash_password(new_password)?;
-        self.password = hashed_password;
-        self.updated_at = DateTimeUtc::from(chrono::Utc::now());
+    /// Replaces the password without the current one; the caller must have
+    /// proven ownership some other way, such as a reset token.
+    pub fn reset_password(
+        &mut self,
+        new_password: &str,
+        hasher: &dyn PasswordHasher,
+        normalization: &PasswordNormalization,
+        identifier_policy: &PasswordIdentifierPolicy,
+        clock: &dyn Clock,
+    ) -> Result<(), DomainError> {
+        let new_password = normalization.apply(new_password);
+        identifier_policy.check(&new_password, &self.email)?;
+        self.password = Some(hasher.hash(&new_password)?);
+        self.clear_failed_logins();
+        self.updated_at = clock.now();
         Ok(())
     }
+
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    /// Whether the email is still taken: always for a live account, and for
+    /// `grace` after a deletion.
+    pub fn holds_email(&self, grace: chrono::Duration, clock: &dyn Clock) -> bool {
+        self.deleted_at
+            .is_none_or(|deleted_at| clock.now() < deleted_at + grace)
+    }
+
+    pub fn is_locked(&self, clock: &dyn Clock) -> bool {
+        self.locked_until
+            .is_some_and(|locked_until| clock.now() < locked_until)
+    }
+
+    /// Returns whether there was anything to clear.
+    pub fn clear_failed_logins(&mut self) -> bool {
+        let had_failures = self.failed_login_attempts != 0 || self.locked_until.is_some();
+        self.failed_login_attempts = 0;
+        self.locked_until = None;
+        had_failures
+    }
+
+    /// Switches to an email the user has just proven, so it is verified.
+    pub fn change_email(&mut self, new_email: &str, clock: &dyn Clock) {
+        let now = clock.now();
+        self.email = new_email.to_lowercase();
+        self.email_verified_at = Some(now);
+        self.updated_at = now;
+    }
+
+    /// Keeps the first verification time if the email is verified again.
+    pub fn verify_email(&mut self, clock: &dyn Clock) {
+        if self.email_verified_at.is_none() {
+            let now = clock.now();
+            self.email_verified_at = Some(now);
+            self.updated_at = now;
+        }
+    }
+
+    pub fn update_preferences(
+        &mut self,
+        locale: Option<String>,
+        timezone: Option<String>,
+        clock: &dyn Clock,
+    ) {
+        self.locale = locale;
+        self.timezone = timezone;
+        self.updated_at = clock.now();
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct UserProfile {
     pub id: String,
     pub email: String,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    // Defaulted so profiles stored in sessions before the field existed
+    // still deserialize.
+    #[serde(default)]
+    pub email_verified: bool,
 }
 
 impl From<User> for UserProfile {
@@ -73,6 +256,79 @@ impl From<User> for UserProfile {
         Self {
             id: value.id,
             email: value.email,
+            locale: value.locale,
+            timezone: value.timezone,
+            email_verified: value.email_verified_at.is_some(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::clock::SystemClock;
+    use crate::domain::password::BcryptHasher;
+
+    const EMAIL: &str = "john@example.com";
+
+    fn hasher() -> BcryptHasher {
+        BcryptHasher { cost: 4 }
+    }
+
+    #[test]
+    fn normalized_password_matches_in_either_unicode_form() {
+        let normalization = PasswordNormalization {
+            unicode: true,
+            trim: false,
+        };
+        let user = User::create_new_user(
+            EMAIL,
+            "caf\u{e9} secret",
+            &hasher(),
+            &normalization,
+            &PasswordIdentifierPolicy::default(),
+            &SystemClock,
+        )
+        .unwrap();
+
+        assert!(user.is_password_match("cafe\u{301} secret", &normalization).is_ok());
+    }
+
+    #[test]
+    fn unnormalized_password_needs_the_same_unicode_form() {
+        let normalization = PasswordNormalization::default();
+        let user = User::create_new_user(
+            EMAIL,
+            "caf\u{e9} secret",
+            &hasher(),
+            &normalization,
+            &PasswordIdentifierPolicy::default(),
+            &SystemClock,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            user.is_password_match("cafe\u{301} secret", &normalization),
+            Err(DomainError::PasswordNotMatchError)
+        ));
+    }
+
+    #[test]
+    fn debug_output_redacts_password_hash() {
+        let user = User::create_new_user(
+            EMAIL,
+            "securePassword123!",
+            &hasher(),
+            &PasswordNormalization::default(),
+            &PasswordIdentifierPolicy::default(),
+            &SystemClock,
+        )
+        .unwrap();
+        let hash = user.password.clone().unwrap();
+
+        let debug = format!("{:?}", user);
+
+        assert!(debug.contains("[REDACTED]"));
+        assert!(!debug.contains(&hash));
+    }
+}
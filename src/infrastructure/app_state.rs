@@ -13,43 +13,216 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-use crate::application::auth::api::auth_service::{AuthService, DefaultAuthService};
+use crate::application::auth::api::auth_service::{
+    AuthService, BreachedPasswordMode, DefaultAuthService,
+};
+use crate::application::auth::spi::oauth_provider::OAuthProvider;
 use crate::application::health::api::health_service::{HealthService, HealthServiceImpl};
+use crate::application::session::api::session_service::{DefaultSessionService, SessionService};
 use crate::application::user::api::user_service::DefaultUserService;
+use crate::application::user::password_hashing::PasswordHashingLimiter;
+use crate::domain::clock::{Clock, SystemClock};
+use crate::domain::lockout::LockoutPolicy;
+use crate::domain::password::{
+    PasswordHashAlgorithm, PasswordIdentifierPolicy, PasswordNormalization,
+};
 use crate::infrastructure::application_health::ApplicationHealth;
+use crate::infrastructure::auth::google_oauth::GoogleOAuthProvider;
+use crate::infrastructure::auth::token_service::TokenService;
+use crate::infrastructure::config::{env_flag, env_or, env_parse, env_var};
+use crate::infrastructure::event_bus::{spawn_event_logger, BroadcastEventBus};
+use crate::infrastructure::http_client::build_http_client;
+use crate::infrastructure::login_rate_limiter::LoginRateLimiter;
+use crate::infrastructure::magic_link_rate_limiter::MagicLinkRateLimiter;
+use crate::infrastructure::mx_email_domain::MxEmailDomainChecker;
 use crate::infrastructure::persistence::seaorm::db::establish_connection;
+use crate::infrastructure::persistence::seaorm::repository::email_verification_repository::SeaOrmEmailVerificationRepository;
+use crate::infrastructure::persistence::seaorm::repository::magic_link_repository::SeaOrmMagicLinkRepository;
+use crate::infrastructure::persistence::seaorm::repository::oauth_identity_repository::SeaOrmOAuthIdentityRepository;
+use crate::infrastructure::persistence::seaorm::repository::password_reset_repository::SeaOrmPasswordResetRepository;
+use crate::infrastructure::persistence::seaorm::repository::pending_email_change_repository::SeaOrmPendingEmailChangeRepository;
+use crate::infrastructure::persistence::seaorm::repository::refresh_token_repository::SeaOrmRefreshTokenRepository;
+use crate::infrastructure::persistence::seaorm::repository::session_repository::SeaOrmSessionRepository;
 use crate::infrastructure::persistence::seaorm::repository::user_repository::SeaOrmUserRepository;
+use crate::infrastructure::persistence::seaorm::retry::DbRetryPolicy;
+use crate::infrastructure::pwned_passwords::PwnedPasswordsClient;
+use crate::infrastructure::resilient_session_store::ResilientSessionStore;
+use crate::infrastructure::session_encryption::EncryptingSessionStore;
 use anyhow;
+use tower_sessions_redis_store::fred::prelude::Pool;
+use tower_sessions_redis_store::RedisStore;
+
+pub type AppSessionStore = ResilientSessionStore<EncryptingSessionStore<RedisStore<Pool>>>;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct AppState {
     pub health_service: Arc<dyn HealthService>,
     pub auth_service: Arc<dyn AuthService>,
+    pub session_service: Arc<dyn SessionService>,
+    pub event_bus: Arc<BroadcastEventBus>,
+    pub clock: Arc<dyn Clock>,
+    pub http_client: reqwest::Client,
+    pub session_store: AppSessionStore,
+    pub single_active_session: bool,
+    pub reauth_max_age: Option<Duration>,
+    /// Set when `JWT_SECRET` enables access-token authentication.
+    pub token_service: Option<Arc<TokenService>>,
+    /// Unset when `LOGIN_MAX_FAILURES` is 0.
+    pub login_rate_limiter: Option<Arc<LoginRateLimiter>>,
+    /// Unset when `MAGIC_LINK_MAX_REQUESTS` is 0.
+    pub magic_link_rate_limiter: Option<Arc<MagicLinkRateLimiter>>,
+    /// Where the browser lands after signing in through an OAuth provider.
+    pub oauth_success_redirect: String,
+    /// Set when `SESSION_COOKIE_FAST_REJECT` is on: session authentication
+    /// without this cookie fails before the session store is touched.
+    pub required_session_cookie: Option<String>,
 }
 
 impl AppState {
-    pub async fn initialize_app_state() -> anyhow::Result<Self> {
+    pub async fn initialize_app_state(
+        session_store: AppSessionStore,
+        redis_pool: Pool,
+        required_session_cookie: Option<String>,
+    ) -> anyhow::Result<Self> {
         // Health module
-        let application_health = Arc::new(ApplicationHealth::default());
+        let application_health = Arc::new(ApplicationHealth::new(
+            session_store.clone(),
+            Duration::from_millis(env_parse("HEALTH_SESSION_STORE_DEGRADED_MS", 100)?),
+        ));
         let health_service = Arc::new(HealthServiceImpl {
             health_repository: application_health,
         });
 
         let db_connection = establish_connection().await?;
+        let db_retry = DbRetryPolicy::from_env()?;
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let password_hashing = PasswordHashingLimiter::new(env_parse(
+            "PASSWORD_HASH_CONCURRENCY",
+            PasswordHashingLimiter::default_concurrency(),
+        )?);
+
+        // Outbound HTTP
+        let http_client = build_http_client()?;
+
+        // Event bus
+        let event_bus = Arc::new(BroadcastEventBus::new());
+        spawn_event_logger(event_bus.subscribe());
 
         // Auth module
         let user_repository = Arc::new(SeaOrmUserRepository {
-            db: db_connection.clone(),
+            db: db_connection.primary.clone(),
+            retry: db_retry,
+        });
+        let user_read_repository = Arc::new(SeaOrmUserRepository {
+            db: db_connection.replica.clone(),
+            retry: db_retry,
         });
         let user_service = Arc::new(DefaultUserService {
             user_repository: user_repository.clone(),
+            user_read_repository,
+            clock: clock.clone(),
+            password_hashing,
+            password_hasher: env_parse("PASSWORD_HASH_ALGORITHM", PasswordHashAlgorithm::Argon2)?
+                .hasher(),
+            password_normalization: PasswordNormalization {
+                unicode: env_flag("PASSWORD_NORMALIZE_UNICODE", false)?,
+                trim: env_flag("PASSWORD_TRIM_WHITESPACE", false)?,
+            },
+            password_identifier_policy: PasswordIdentifierPolicy {
+                enabled: env_flag("PASSWORD_REJECT_EMAIL", false)?,
+                min_match_length: env_parse("PASSWORD_EMAIL_MATCH_MIN_LENGTH", 4)?,
+            },
+            lockout_policy: LockoutPolicy {
+                threshold: env_parse("LOGIN_LOCKOUT_THRESHOLD", 10)?,
+                base: chrono::Duration::seconds(env_parse("LOGIN_LOCKOUT_BASE_SECS", 60)?),
+                max: chrono::Duration::seconds(env_parse("LOGIN_LOCKOUT_MAX_SECS", 86_400)?),
+            },
+            deletion_grace: chrono::Duration::days(env_parse("USER_DELETION_GRACE_DAYS", 30)?),
+        });
+        let mut oauth_providers: HashMap<String, Arc<dyn OAuthProvider>> = HashMap::new();
+        if let Some(google) = GoogleOAuthProvider::from_env(http_client.clone())? {
+            oauth_providers.insert(google.name().to_string(), Arc::new(google));
+        }
+        let auth_service = Arc::new(DefaultAuthService {
+            user_service,
+            event_publisher: event_bus.clone(),
+            clock: clock.clone(),
+            disclose_email_availability: env_flag("REGISTER_VALIDATE_DISCLOSE_EMAIL", false)?,
+            breached_password_checker: Arc::new(PwnedPasswordsClient::from_env(
+                http_client.clone(),
+            )?),
+            breached_password_mode: env_parse("PWNED_PASSWORD_CHECK", BreachedPasswordMode::Off)?,
+            email_domain_checker: if env_flag("REGISTER_REQUIRE_EMAIL_MX", false)? {
+                Some(Arc::new(MxEmailDomainChecker::from_env()?))
+            } else {
+                None
+            },
+            password_reset_repository: Arc::new(SeaOrmPasswordResetRepository {
+                db: db_connection.primary.clone(),
+                retry: db_retry,
+            }),
+            email_verification: env_flag("EMAIL_VERIFICATION", false)?,
+            email_verification_repository: Arc::new(SeaOrmEmailVerificationRepository {
+                db: db_connection.primary.clone(),
+                retry: db_retry,
+            }),
+            refresh_token_repository: Arc::new(SeaOrmRefreshTokenRepository {
+                db: db_connection.primary.clone(),
+                retry: db_retry,
+            }),
+            refresh_token_ttl: chrono::Duration::seconds(env_parse(
+                "JWT_REFRESH_TOKEN_TTL_SECS",
+                30 * 24 * 60 * 60,
+            )?),
+            oauth_providers,
+            oauth_identity_repository: Arc::new(SeaOrmOAuthIdentityRepository {
+                db: db_connection.primary.clone(),
+                retry: db_retry,
+            }),
+            magic_link_repository: Arc::new(SeaOrmMagicLinkRepository {
+                db: db_connection.primary.clone(),
+                retry: db_retry,
+            }),
+            pending_email_change_repository: Arc::new(SeaOrmPendingEmailChangeRepository {
+                db: db_connection.primary.clone(),
+                retry: db_retry,
+            }),
+        });
+
+        // Session module
+        let session_repository = Arc::new(SeaOrmSessionRepository {
+            db: db_connection.primary.clone(),
+            retry: db_retry,
+        });
+        let session_service = Arc::new(DefaultSessionService {
+            session_repository,
+            clock: clock.clone(),
         });
-        let auth_service = Arc::new(DefaultAuthService { user_service });
 
         Ok(AppState {
             health_service,
             auth_service,
+            session_service,
+            event_bus,
+            http_client,
+            session_store,
+            single_active_session: env_flag("SESSION_SINGLE_ACTIVE", false)?,
+            reauth_max_age: env_var("REAUTH_MAX_AGE_SECS")
+                .map(|value| {
+                    value.parse().map(Duration::from_secs).map_err(|e| {
+                        anyhow::anyhow!("Invalid REAUTH_MAX_AGE_SECS environment variable: {}", e)
+                    })
+                })
+                .transpose()?,
+            token_service: TokenService::from_env(clock.clone())?.map(Arc::new),
+            login_rate_limiter: LoginRateLimiter::from_env(redis_pool.clone())?.map(Arc::new),
+            magic_link_rate_limiter: MagicLinkRateLimiter::from_env(redis_pool)?.map(Arc::new),
+            oauth_success_redirect: env_or("OAUTH_SUCCESS_REDIRECT", "/"),
+            required_session_cookie,
+            clock,
         })
     }
 }
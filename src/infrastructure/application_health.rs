@@ -15,24 +15,57 @@
  */
 use crate::application::health::spi::health_repository::HealthRepository;
 use crate::domain::health::Health;
+use crate::infrastructure::app_state::AppSessionStore;
+use std::time::{Duration, Instant};
+use tower_sessions::SessionStore;
+use tower_sessions::session::{Id, Record};
 
-pub struct ApplicationHealth {}
+pub struct ApplicationHealth {
+    pub session_store: AppSessionStore,
+    pub degraded_threshold: Duration,
+}
 
 impl ApplicationHealth {
-    pub fn new() -> ApplicationHealth {
-        ApplicationHealth {}
+    pub fn new(session_store: AppSessionStore, degraded_threshold: Duration) -> ApplicationHealth {
+        ApplicationHealth {
+            session_store,
+            degraded_threshold,
+        }
     }
-}
 
-impl Default for ApplicationHealth {
-    fn default() -> Self {
-        ApplicationHealth::new()
+    // A full write/read/delete cycle through the session store, which catches
+    // a slow Redis that still answers PING.
+    async fn session_store_round_trip(&self) -> anyhow::Result<Duration> {
+        let started = Instant::now();
+        let mut record = Record {
+            id: Id::default(),
+            data: Default::default(),
+            expiry_date: time::OffsetDateTime::now_utc() + time::Duration::minutes(1),
+        };
+        self.session_store.create(&mut record).await?;
+        self.session_store.load(&record.id).await?;
+        self.session_store.delete(&record.id).await?;
+        Ok(started.elapsed())
     }
 }
 
 #[async_trait::async_trait]
 impl HealthRepository for ApplicationHealth {
     async fn health_check(&self) -> Health {
-        Health { healthy: true }
+        match self.session_store_round_trip().await {
+            Ok(latency) => Health {
+                healthy: true,
+                degraded: latency > self.degraded_threshold,
+                session_store_latency_ms: Some(latency.as_millis() as u64),
+            },
+            Err(e) => {
+                tracing::warn!("Session store health check failed: {}", e);
+                Health {
+                    healthy: false,
+                    degraded: false,
+                    session_store_latency_ms: None,
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,118 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::auth::spi::oauth_provider::OAuthProvider;
+use crate::domain::oauth::OAuthUserInfo;
+use crate::infrastructure::config::{env_or, env_or_file, env_var};
+
+const AUTHORIZATION_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const USERINFO_URL: &str = "https://openidconnect.googleapis.com/v1/userinfo";
+
+/// Google sign-in through the OAuth2 authorization code flow.
+pub struct GoogleOAuthProvider {
+    pub client: reqwest::Client,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+}
+
+impl GoogleOAuthProvider {
+    /// Returns `None` unless `GOOGLE_CLIENT_ID` is set.
+    pub fn from_env(client: reqwest::Client) -> anyhow::Result<Option<Self>> {
+        let Some(client_id) = env_var("GOOGLE_CLIENT_ID") else {
+            return Ok(None);
+        };
+        let client_secret = env_or_file("GOOGLE_CLIENT_SECRET")?.ok_or_else(|| {
+            anyhow::anyhow!("GOOGLE_CLIENT_SECRET must be set when GOOGLE_CLIENT_ID is")
+        })?;
+        Ok(Some(Self {
+            client,
+            client_id,
+            client_secret,
+            redirect_uri: env_or(
+                "GOOGLE_REDIRECT_URI",
+                "http://localhost:3000/auth/oauth/google/callback",
+            ),
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl OAuthProvider for GoogleOAuthProvider {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    fn authorization_url(&self, state: &str) -> String {
+        reqwest::Url::parse_with_params(
+            AUTHORIZATION_URL,
+            &[
+                ("client_id", self.client_id.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("response_type", "code"),
+                ("scope", "openid email"),
+                ("state", state),
+            ],
+        )
+        .map(String::from)
+        .unwrap_or_else(|_| AUTHORIZATION_URL.to_string())
+    }
+
+    async fn exchange_code(&self, code: &str) -> anyhow::Result<OAuthUserInfo> {
+        let token_body = self
+            .client
+            .post(TOKEN_URL)
+            .form(&[
+                ("code", code),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("grant_type", "authorization_code"),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let token: serde_json::Value = serde_json::from_str(&token_body)?;
+        let access_token = token["access_token"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Google token response has no access_token"))?;
+
+        let userinfo_body = self
+            .client
+            .get(USERINFO_URL)
+            .bearer_auth(access_token)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let userinfo: serde_json::Value = serde_json::from_str(&userinfo_body)?;
+        let subject = userinfo["sub"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Google userinfo has no sub"))?;
+        let email = userinfo["email"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Google userinfo has no email"))?;
+        Ok(OAuthUserInfo {
+            subject: subject.to_string(),
+            email: email.to_lowercase(),
+            email_verified: userinfo["email_verified"].as_bool().unwrap_or(false),
+        })
+    }
+}
@@ -0,0 +1,210 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::clock::Clock;
+use crate::domain::user::UserProfile;
+use crate::infrastructure::config::{env_or_file, env_parse};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+// HS256 keys shorter than the hash output weaken the signature.
+const MIN_SECRET_LENGTH: usize = 32;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AccessTokenClaims {
+    sub: String,
+    email: String,
+    iat: i64,
+    exp: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timezone: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+}
+
+#[derive(Debug)]
+pub struct AccessToken {
+    pub token: String,
+    pub expires_in: u64,
+}
+
+/// Signs and verifies stateless access tokens carrying the user profile, for
+/// clients that cannot keep a session cookie.
+pub struct TokenService {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    validation: Validation,
+    ttl: Duration,
+    leeway: Duration,
+    clock: Arc<dyn Clock>,
+}
+
+impl TokenService {
+    pub fn new(secret: &[u8], ttl: Duration, leeway: Duration, clock: Arc<dyn Clock>) -> Self {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.set_required_spec_claims(&["exp", "sub"]);
+        // Expiry is checked against the injected clock in `verify`.
+        validation.validate_exp = false;
+        Self {
+            encoding_key: EncodingKey::from_secret(secret),
+            decoding_key: DecodingKey::from_secret(secret),
+            validation,
+            ttl,
+            leeway,
+            clock,
+        }
+    }
+
+    /// `None` when `JWT_SECRET` is unset, which disables token authentication.
+    pub fn from_env(clock: Arc<dyn Clock>) -> anyhow::Result<Option<Self>> {
+        let Some(secret) = env_or_file("JWT_SECRET")? else {
+            return Ok(None);
+        };
+        if secret.len() < MIN_SECRET_LENGTH {
+            anyhow::bail!(
+                "JWT_SECRET must be at least {} bytes long",
+                MIN_SECRET_LENGTH
+            );
+        }
+        let ttl = Duration::from_secs(env_parse("JWT_ACCESS_TOKEN_TTL_SECS", 900)?);
+        let leeway = Duration::from_secs(env_parse("JWT_LEEWAY_SECS", 30)?);
+        Ok(Some(Self::new(secret.as_bytes(), ttl, leeway, clock)))
+    }
+
+    pub fn issue(&self, profile: &UserProfile) -> anyhow::Result<AccessToken> {
+        let now = self.clock.now().timestamp();
+        let claims = AccessTokenClaims {
+            sub: profile.id.clone(),
+            email: profile.email.clone(),
+            iat: now,
+            exp: now + self.ttl.as_secs() as i64,
+            locale: profile.locale.clone(),
+            timezone: profile.timezone.clone(),
+            email_verified: profile.email_verified,
+        };
+        let token =
+            jsonwebtoken::encode(&Header::new(Algorithm::HS256), &claims, &self.encoding_key)?;
+        Ok(AccessToken {
+            token,
+            expires_in: self.ttl.as_secs(),
+        })
+    }
+
+    pub fn verify(&self, token: &str) -> Result<UserProfile, jsonwebtoken::errors::Error> {
+        let claims =
+            jsonwebtoken::decode::<AccessTokenClaims>(token, &self.decoding_key, &self.validation)?
+                .claims;
+        let now = self.clock.now().timestamp();
+        if claims.exp.saturating_add(self.leeway.as_secs() as i64) < now {
+            return Err(ErrorKind::ExpiredSignature.into());
+        }
+        Ok(UserProfile {
+            id: claims.sub,
+            email: claims.email,
+            locale: claims.locale,
+            timezone: claims.timezone,
+            email_verified: claims.email_verified,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use chrono::{TimeZone, Utc};
+
+    const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn profile() -> UserProfile {
+        UserProfile {
+            id: "user-id".to_string(),
+            email: "john@example.com".to_string(),
+            locale: None,
+            timezone: None,
+            email_verified: true,
+        }
+    }
+
+    fn fixed_clock() -> Arc<FixedClock> {
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        Arc::new(FixedClock::new(now.into()))
+    }
+
+    fn service(clock: Arc<FixedClock>) -> TokenService {
+        TokenService::new(
+            SECRET,
+            Duration::from_secs(900),
+            Duration::from_secs(30),
+            clock,
+        )
+    }
+
+    #[test]
+    fn verify_returns_profile_of_fresh_token() {
+        let clock = fixed_clock();
+        let service = service(clock);
+        let token = service.issue(&profile()).unwrap().token;
+
+        let verified = service.verify(&token).unwrap();
+
+        assert_eq!(verified.id, "user-id");
+        assert_eq!(verified.email, "john@example.com");
+        assert!(verified.email_verified);
+    }
+
+    #[test]
+    fn verify_accepts_token_within_leeway() {
+        let clock = fixed_clock();
+        let service = service(clock.clone());
+        let token = service.issue(&profile()).unwrap().token;
+
+        clock.advance(chrono::Duration::seconds(900 + 30));
+
+        assert!(service.verify(&token).is_ok());
+    }
+
+    #[test]
+    fn verify_rejects_token_past_leeway() {
+        let clock = fixed_clock();
+        let service = service(clock.clone());
+        let token = service.issue(&profile()).unwrap().token;
+
+        clock.advance(chrono::Duration::seconds(900 + 31));
+
+        let error = service.verify(&token).unwrap_err();
+        assert_eq!(error.kind(), &ErrorKind::ExpiredSignature);
+    }
+
+    #[test]
+    fn verify_rejects_token_signed_with_other_secret() {
+        let clock = fixed_clock();
+        let other = TokenService::new(
+            b"fedcba9876543210fedcba9876543210",
+            Duration::from_secs(900),
+            Duration::from_secs(30),
+            clock.clone(),
+        );
+        let token = other.issue(&profile()).unwrap().token;
+
+        assert!(service(clock).verify(&token).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::sync::OnceLock;
+
+static CONFIG_FILE_VALUES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+// Values from CONFIG_FILE are the lowest precedence layer: any variable set in the
+// environment (including via .env) overrides them.
+pub fn load_config_file() -> anyhow::Result<()> {
+    let Ok(path) = env::var("CONFIG_FILE") else {
+        return Ok(());
+    };
+    let content = fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("Failed to read CONFIG_FILE {}: {}", path, e))?;
+    let table: toml::Table = content
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid CONFIG_FILE {}: {}", path, e))?;
+
+    let mut values = HashMap::new();
+    flatten_table("", &table, &mut values)?;
+    CONFIG_FILE_VALUES
+        .set(values)
+        .map_err(|_| anyhow::anyhow!("CONFIG_FILE has already been loaded"))
+}
+
+fn flatten_table(
+    prefix: &str,
+    table: &toml::Table,
+    values: &mut HashMap<String, String>,
+) -> anyhow::Result<()> {
+    for (key, value) in table {
+        let name = format!("{}{}", prefix, key.to_uppercase());
+        match value {
+            toml::Value::Table(nested) => flatten_table(&format!("{}_", name), nested, values)?,
+            toml::Value::Array(items) => {
+                let items = items
+                    .iter()
+                    .map(|item| scalar_to_string(&name, item))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                values.insert(name, items.join(","));
+            }
+            scalar => {
+                let value = scalar_to_string(&name, scalar)?;
+                values.insert(name, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn scalar_to_string(name: &str, value: &toml::Value) -> anyhow::Result<String> {
+    match value {
+        toml::Value::String(value) => Ok(value.clone()),
+        toml::Value::Integer(value) => Ok(value.to_string()),
+        toml::Value::Float(value) => Ok(value.to_string()),
+        toml::Value::Boolean(value) => Ok(value.to_string()),
+        toml::Value::Datetime(value) => Ok(value.to_string()),
+        _ => Err(anyhow::anyhow!(
+            "Unsupported value for {} in CONFIG_FILE",
+            name
+        )),
+    }
+}
+
+pub fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().or_else(|| {
+        CONFIG_FILE_VALUES
+            .get()
+            .and_then(|values| values.get(name).cloned())
+    })
+}
+
+pub fn env_or_file(name: &str) -> anyhow::Result<Option<String>> {
+    let file_var = format!("{}_FILE", name);
+    if let Some(path) = env_var(&file_var) {
+        let content = fs::read_to_string(&path).map_err(|e| {
+            anyhow::anyhow!("Failed to read {} from file {}: {}", file_var, path, e)
+        })?;
+        let value = content.trim_end_matches(['\n', '\r']);
+        if value.is_empty() {
+            return Err(anyhow::anyhow!(
+                "{} points to an empty file: {}",
+                file_var,
+                path
+            ));
+        }
+        return Ok(Some(value.to_string()));
+    }
+    Ok(env_var(name))
+}
+
+pub fn env_or(name: &str, default: &str) -> String {
+    env_var(name).unwrap_or_else(|| default.to_string())
+}
+
+pub fn env_flag(name: &str, default: bool) -> anyhow::Result<bool> {
+    match env_var(name) {
+        Some(value) => match value.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(true),
+            "0" | "false" | "no" | "off" => Ok(false),
+            _ => Err(anyhow::anyhow!(
+                "Invalid {} environment variable: {}",
+                name,
+                value
+            )),
+        },
+        None => Ok(default),
+    }
+}
+
+pub fn env_parse<T>(name: &str, default: T) -> anyhow::Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match env_var(name) {
+        Some(value) => value
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid {} environment variable: {}", name, e)),
+        None => Ok(default),
+    }
+}
+
+const NON_PRODUCTION_ENVIRONMENTS: [&str; 4] = ["development", "dev", "local", "test"];
+
+/// Anything but an explicit non-production `ENVIRONMENT` counts as production,
+/// so `prod` or `staging` do not leak debug output.
+pub fn is_production() -> bool {
+    let environment = env_or("ENVIRONMENT", "production");
+    !NON_PRODUCTION_ENVIRONMENTS
+        .iter()
+        .any(|name| environment.trim().eq_ignore_ascii_case(name))
+}
+
+/// True only when `ENVIRONMENT` is set and names a production environment;
+/// an unset `ENVIRONMENT` is not a declaration.
+pub fn is_declared_production() -> bool {
+    env_var("ENVIRONMENT").is_some() && is_production()
+}
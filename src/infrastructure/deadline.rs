@@ -0,0 +1,32 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::future::Future;
+use tokio::time::Instant;
+
+tokio::task_local! {
+    static REQUEST_DEADLINE: Instant;
+}
+
+/// Runs `future` with `deadline` visible to everything it awaits on the same
+/// task, so downstream calls can give up once the request has timed out.
+pub async fn with_deadline<F: Future>(deadline: Instant, future: F) -> F::Output {
+    REQUEST_DEADLINE.scope(deadline, future).await
+}
+
+pub fn current_deadline() -> Option<Instant> {
+    REQUEST_DEADLINE.try_with(|deadline| *deadline).ok()
+}
@@ -0,0 +1,64 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::event::spi::event_publisher::EventPublisher;
+use crate::domain::event::DomainEvent;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+const EVENT_BUS_CAPACITY: usize = 1024;
+
+pub struct BroadcastEventBus {
+    sender: broadcast::Sender<DomainEvent>,
+}
+
+impl BroadcastEventBus {
+    pub fn new() -> BroadcastEventBus {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        BroadcastEventBus { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<DomainEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for BroadcastEventBus {
+    fn default() -> Self {
+        BroadcastEventBus::new()
+    }
+}
+
+impl EventPublisher for BroadcastEventBus {
+    fn publish(&self, event: DomainEvent) {
+        // Sending only fails when nobody is subscribed, which is not an error for the publisher.
+        let _ = self.sender.send(event);
+    }
+}
+
+pub fn spawn_event_logger(mut receiver: broadcast::Receiver<DomainEvent>) {
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => tracing::debug!("Domain event: {:?}", event),
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Event logger lagged, skipped {} events", skipped)
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -13,19 +13,147 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use crate::domain::common::{DateTimeUtc, DomainError};
+use crate::domain::session::SessionMetadata;
+use crate::domain::secret_token::{hash_token, SecretToken, TokenKind};
 use crate::domain::user::UserProfile;
 use crate::infrastructure::app_state::AppState;
-use crate::infrastructure::http::common::auth::{AuthenticatedUser, SESSION_USER_KEY};
-use crate::infrastructure::http::common::validator::ValidatedJson;
+use crate::infrastructure::auth::token_service::TokenService;
+use crate::infrastructure::http::common::auth::{
+    delete_session, mark_authenticated, require_recent_auth, supersede_session, AuthSource,
+    AuthenticatedUser, SESSION_USER_KEY,
+};
+use crate::infrastructure::http::common::validator::{
+    normalize_email, validate_locale, validate_timezone, UnvalidatedJson, ValidatedJson,
+};
 use crate::infrastructure::http::error_handler::{ApiError, ApiResult, ErrorKind};
-use axum::extract::State;
+use axum::extract::{ConnectInfo, Path, Query, State};
+use axum::http::header::USER_AGENT;
+use axum::http::HeaderMap;
+use axum::response::Redirect;
 use axum::Json;
 use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tower_sessions::Session;
-use utoipa::ToSchema;
+use tower_sessions::session::Id;
+use tower_sessions::{Session, SessionStore};
+use utoipa::{IntoParams, ToSchema};
+use validator::{Validate, ValidationErrors};
 
 const AUTH_TAG: &str = "Auth";
+// RFC 5321 path limit, below the VARCHAR(255) email column.
+const MAX_EMAIL_LENGTH: u64 = 254;
+const SESSION_OAUTH_STATE_KEY: &str = "oauth_state";
+
+async fn start_user_session(
+    app_state: &AppState,
+    session: &Session,
+    headers: &HeaderMap,
+    client_address: SocketAddr,
+    current_user: &UserProfile,
+) -> Result<(), ApiError> {
+    // A session that existed before sign-in gets a fresh id, so an id planted
+    // or seen before login does not become an authenticated one.
+    if let Some(previous_session_id) = session.id() {
+        let removed = app_state
+            .session_service
+            .remove_session(&previous_session_id.to_string())
+            .await;
+        if let Err(e) = removed {
+            tracing::warn!("Failed to remove previous session metadata: {}", e);
+        }
+    }
+    session.cycle_id().await.map_err(|_| {
+        ApiError::new(
+            "failed_to_create_session_error".to_string(),
+            ErrorKind::InternalServerError,
+        )
+    })?;
+    session
+        .insert(SESSION_USER_KEY, current_user)
+        .await
+        .map_err(|_| {
+            ApiError::new(
+                "failed_to_create_session_error".to_string(),
+                ErrorKind::InternalServerError,
+            )
+        })?;
+    mark_authenticated(session, app_state.clock.as_ref()).await?;
+    session.save().await.map_err(|_| {
+        ApiError::new(
+            "failed_to_create_session_error".to_string(),
+            ErrorKind::InternalServerError,
+        )
+    })?;
+
+    if let Some(session_id) = session.id() {
+        let user_agent = headers
+            .get(USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let recorded = app_state
+            .session_service
+            .record_session(
+                &session_id.to_string(),
+                &current_user.id,
+                user_agent,
+                Some(client_address.ip().to_string()),
+            )
+            .await;
+        if let Err(e) = recorded {
+            tracing::warn!("Failed to record session metadata: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+// Single active session policy: every other session of the user is logged
+// out. Failures are logged only, the new login itself has succeeded.
+async fn supersede_other_sessions(app_state: &AppState, session: &Session, user_id: &str) {
+    let Some(current_session_id) = session.id().map(|id| id.to_string()) else {
+        return;
+    };
+    let sessions = match app_state.session_service.list_sessions(user_id).await {
+        Ok(sessions) => sessions,
+        Err(e) => {
+            tracing::warn!("Failed to list sessions to supersede: {}", e);
+            return;
+        }
+    };
+
+    for other in sessions.iter().filter(|s| s.id != current_session_id) {
+        if let Err(e) = supersede_session(&app_state.session_store, &other.id).await {
+            tracing::warn!("Failed to supersede session: {}", e);
+            continue;
+        }
+        if let Err(e) = app_state.session_service.remove_session(&other.id).await {
+            tracing::warn!("Failed to remove superseded session metadata: {}", e);
+        }
+    }
+}
+
+/// How a successful login or registration is carried: a session cookie, or
+/// an access token in the response body for clients without cookies.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMode {
+    #[default]
+    Session,
+    Token,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    #[schema(example = "Bearer")]
+    pub token_type: String,
+    /// Lifetime of the access token in seconds.
+    #[schema(example = 900)]
+    pub expires_in: u64,
+    /// Single use: exchange it at /auth/refresh for a new pair.
+    pub refresh_token: String,
+}
 
 #[derive(Serialize, Debug, ToSchema)]
 pub struct AuthResponse {
@@ -33,27 +161,87 @@ pub struct AuthResponse {
     pub id: String,
     #[schema(example = "john.doe@example.com")]
     pub email: String,
+    /// Present only when `auth_mode` is `token`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<TokenResponse>,
+}
+
+fn require_token_service(app_state: &AppState) -> Result<&TokenService, ApiError> {
+    app_state.token_service.as_deref().ok_or_else(|| {
+        ApiError::new(
+            "token_auth_disabled_error".to_string(),
+            ErrorKind::BadRequest,
+        )
+    })
+}
+
+fn token_service_for(
+    app_state: &AppState,
+    mode: AuthMode,
+) -> Result<Option<&TokenService>, ApiError> {
+    match mode {
+        AuthMode::Session => Ok(None),
+        AuthMode::Token => require_token_service(app_state).map(Some),
+    }
+}
+
+fn token_response(
+    token_service: &TokenService,
+    current_user: &UserProfile,
+    refresh_token: SecretToken,
+) -> Result<TokenResponse, ApiError> {
+    let access_token = token_service.issue(current_user).map_err(|e| {
+        tracing::error!("Failed to sign access token: {}", e);
+        ApiError::new(
+            "failed_to_issue_token_error".to_string(),
+            ErrorKind::InternalServerError,
+        )
+    })?;
+    Ok(TokenResponse {
+        access_token: access_token.token,
+        token_type: "Bearer".to_string(),
+        expires_in: access_token.expires_in,
+        refresh_token: refresh_token.0,
+    })
+}
+
+async fn issue_tokens(
+    app_state: &AppState,
+    token_service: &TokenService,
+    current_user: &UserProfile,
+) -> Result<TokenResponse, ApiError> {
+    let refresh_token = app_state
+        .auth_service
+        .issue_refresh_token(&current_user.id)
+        .await?;
+    token_response(token_service, current_user, refresh_token)
 }
 
 #[derive(Deserialize, Debug, ToSchema, validator::Validate)]
 pub struct RegisterRequest {
-    #[validate(email(message = "invalid_email_format"))]
+    #[serde(deserialize_with = "normalize_email")]
+    #[validate(
+        email(message = "invalid_email_format"),
+        length(max = MAX_EMAIL_LENGTH, message = "email_too_long")
+    )]
     #[schema(example = "john.doe@example.com")]
     pub email: String,
     #[validate(length(min = 8, message = "password_must_be_at_least_8_characters"))]
     #[schema(example = "securePassword123!")]
     pub password: String,
+    #[serde(default)]
+    pub auth_mode: AuthMode,
 }
 
 #[utoipa::path(
     tag = AUTH_TAG,
     post,
     path = "/auth/register",
-    description = "Register a new user account with email and password. Creates a new user session upon successful registration.",
+    description = "Register a new user account with email and password. Creates a new user session upon successful registration, or returns an access token instead when auth_mode is token.",
     request_body = RegisterRequest,
     responses(
         (status = 201, description = "User registered successfully", body = AuthResponse),
-        (status = 400, description = "Validation error - check email format and password length", body = ApiError),
+        (status = 400, description = "Validation error - check email format and password length, the password appears in a known breach, or token auth is disabled", body = ApiError),
         (status = 409, description = "User already exists with this email", body = ApiError),
         (status = 500, description = "Internal server error", body = ApiError)
     ),
@@ -61,81 +249,322 @@ pub struct RegisterRequest {
 )]
 pub async fn register(
     State(app_state): State<Arc<AppState>>,
+    ConnectInfo(client_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     session: Session,
     ValidatedJson(request): ValidatedJson<RegisterRequest>,
 ) -> ApiResult<AuthResponse> {
+    let token_service = token_service_for(&app_state, request.auth_mode)?;
     let user = app_state
         .auth_service
         .register(&request.email, &request.password)
         .await?;
 
     let current_user = UserProfile::from(user.clone());
-
-    session
-        .insert(SESSION_USER_KEY, &current_user)
-        .await
-        .map_err(|_| {
-            ApiError::new(
-                "failed_to_create_session_error".to_string(),
-                ErrorKind::InternalServerError,
-            )
-        })?;
+    let token = match token_service {
+        Some(token_service) => Some(issue_tokens(&app_state, token_service, &current_user).await?),
+        None => {
+            start_user_session(&app_state, &session, &headers, client_address, &current_user)
+                .await?;
+            None
+        }
+    };
 
     Ok(Json(AuthResponse {
         id: user.id.to_string(),
         email: user.email,
+        token,
+    }))
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationCheckStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ValidationCheck {
+    pub status: ValidationCheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(example = "invalid_email_format")]
+    pub message: Option<String>,
+}
+
+impl ValidationCheck {
+    fn from_field_errors(errors: Option<&ValidationErrors>, field: &str) -> Self {
+        let message = errors
+            .and_then(|errors| errors.field_errors().get(field).and_then(|e| e.first()))
+            .map(|error| {
+                error
+                    .message
+                    .as_ref()
+                    .map(|msg| msg.to_string())
+                    .unwrap_or_else(|| format!("invalid_{}", field))
+            });
+        match message {
+            Some(message) => Self {
+                status: ValidationCheckStatus::Failed,
+                message: Some(message),
+            },
+            None => Self {
+                status: ValidationCheckStatus::Passed,
+                message: None,
+            },
+        }
+    }
+
+    fn from_availability(available: Option<bool>) -> Self {
+        match available {
+            Some(true) => Self {
+                status: ValidationCheckStatus::Passed,
+                message: None,
+            },
+            Some(false) => Self {
+                status: ValidationCheckStatus::Failed,
+                message: Some("user_already_exists_error".to_string()),
+            },
+            None => Self {
+                status: ValidationCheckStatus::Skipped,
+                message: None,
+            },
+        }
+    }
+
+    /// Maps a service check: a validation error fails it, any other error
+    /// is passed on.
+    fn from_service_check(result: Result<(), DomainError>) -> Result<Self, DomainError> {
+        match result {
+            Ok(()) => Ok(Self {
+                status: ValidationCheckStatus::Passed,
+                message: None,
+            }),
+            Err(DomainError::ValidationError { code, .. }) => Ok(Self {
+                status: ValidationCheckStatus::Failed,
+                message: Some(code),
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn is_failed(&self) -> bool {
+        matches!(self.status, ValidationCheckStatus::Failed)
+    }
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct RegisterValidationResponse {
+    pub valid: bool,
+    pub email_format: ValidationCheck,
+    pub email_available: ValidationCheck,
+    pub password_policy: ValidationCheck,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/register/validate",
+    description = "Dry-run the registration checks (email format and domain, email availability and password policy, including the breach and email checks register enforces) without creating a user. Email availability is reported as skipped unless disclosure is enabled.",
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "Result of each registration check", body = RegisterValidationResponse),
+        (status = 400, description = "Invalid JSON body, or unknown fields when strict JSON is enabled", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "validate_register"
+)]
+pub async fn validate_register(
+    State(app_state): State<Arc<AppState>>,
+    UnvalidatedJson(request): UnvalidatedJson<RegisterRequest>,
+) -> ApiResult<RegisterValidationResponse> {
+    let errors = request.validate().err();
+    let mut email_format = ValidationCheck::from_field_errors(errors.as_ref(), "email");
+    let mut password_policy = ValidationCheck::from_field_errors(errors.as_ref(), "password");
+
+    // The same service checks `register` runs, once the format allows them.
+    if !email_format.is_failed() {
+        email_format = ValidationCheck::from_service_check(
+            app_state
+                .auth_service
+                .validate_registration_email(&request.email)
+                .await,
+        )?;
+    }
+    if !email_format.is_failed() && !password_policy.is_failed() {
+        password_policy = ValidationCheck::from_service_check(
+            app_state
+                .auth_service
+                .validate_registration_password(&request.email, &request.password)
+                .await,
+        )?;
+    }
+
+    let email_available = if email_format.is_failed() {
+        ValidationCheck::from_availability(None)
+    } else {
+        let available = app_state
+            .auth_service
+            .check_email_availability(&request.email)
+            .await?;
+        ValidationCheck::from_availability(available)
+    };
+
+    Ok(Json(RegisterValidationResponse {
+        valid: !email_format.is_failed()
+            && !email_available.is_failed()
+            && !password_policy.is_failed(),
+        email_format,
+        email_available,
+        password_policy,
+    }))
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct CheckPwnedPasswordRequest {
+    #[validate(length(min = 1, message = "password_required"))]
+    #[schema(example = "securePassword123!")]
+    pub password: String,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct CheckPwnedPasswordResponse {
+    /// False when the check is disabled or the breach API is unavailable.
+    pub checked: bool,
+    pub pwned: bool,
+    #[schema(example = 0)]
+    pub breach_count: u64,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/password/check-pwned",
+    description = "Check whether a password appears in known data breaches using the HaveIBeenPwned range API. Only the first 5 characters of the password's SHA-1 hash are sent. Reports checked = false when the check is disabled or the API is unavailable.",
+    request_body = CheckPwnedPasswordRequest,
+    responses(
+        (status = 200, description = "Breach check result", body = CheckPwnedPasswordResponse),
+        (status = 400, description = "Validation error - password is required", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "check_pwned_password"
+)]
+pub async fn check_pwned_password(
+    State(app_state): State<Arc<AppState>>,
+    ValidatedJson(request): ValidatedJson<CheckPwnedPasswordRequest>,
+) -> ApiResult<CheckPwnedPasswordResponse> {
+    let breach_count = app_state
+        .auth_service
+        .check_breached_password(&request.password)
+        .await?;
+
+    Ok(Json(CheckPwnedPasswordResponse {
+        checked: breach_count.is_some(),
+        pwned: breach_count.is_some_and(|count| count > 0),
+        breach_count: breach_count.unwrap_or(0),
     }))
 }
 
 #[derive(Deserialize, Debug, ToSchema, validator::Validate)]
 pub struct LoginRequest {
-    #[validate(email(message = "invalid_email_format"))]
+    #[serde(deserialize_with = "normalize_email")]
+    #[validate(
+        email(message = "invalid_email_format"),
+        length(max = MAX_EMAIL_LENGTH, message = "email_too_long")
+    )]
     #[schema(example = "john.doe@example.com")]
     pub email: String,
     #[validate(length(min = 1, message = "password_required"))]
     #[schema(example = "securePassword123!")]
     pub password: String,
+    #[serde(default)]
+    pub auth_mode: AuthMode,
+}
+
+// Wrong passwords and unknown emails count; outages and the like do not.
+fn is_failed_login(error: &DomainError) -> bool {
+    matches!(
+        error,
+        DomainError::NotFoundError
+            | DomainError::PasswordNotMatchError
+            | DomainError::InvalidCredentials
+            | DomainError::AuthenticationFailed
+    )
 }
 
 #[utoipa::path(
     tag = AUTH_TAG,
     post,
     path = "/auth/login",
-    description = "Authenticate user with email and password credentials. Creates a new user session upon successful authentication.",
+    description = "Authenticate user with email and password credentials. Creates a new user session upon successful authentication, or returns an access token instead when auth_mode is token.",
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successfully", body = AuthResponse),
-        (status = 400, description = "Validation error - check email format", body = ApiError),
+        (status = 400, description = "Validation error - check email format, or token auth is disabled", body = ApiError),
         (status = 401, description = "Invalid email or password", body = ApiError),
+        (status = 429, description = "Too many failed attempts for this email or client; see Retry-After", body = ApiError),
         (status = 500, description = "Internal server error", body = ApiError)
     ),
     operation_id = "login"
 )]
 pub async fn login(
     State(app_state): State<Arc<AppState>>,
+    ConnectInfo(client_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     session: Session,
     ValidatedJson(request): ValidatedJson<LoginRequest>,
 ) -> ApiResult<AuthResponse> {
-    let user = app_state
+    let token_service = token_service_for(&app_state, request.auth_mode)?;
+    let rate_limiter = app_state.login_rate_limiter.as_deref();
+    let client_ip = client_address.ip();
+    if let Some(rate_limiter) = rate_limiter
+        && let Some(retry_after) = rate_limiter.check(&request.email, client_ip).await
+    {
+        return Err(ApiError::new(
+            "too_many_login_attempts_error".to_string(),
+            ErrorKind::TooManyRequests,
+        )
+        .with_retry_after(retry_after.as_secs().max(1)));
+    }
+
+    let user = match app_state
         .auth_service
         .login(&request.email, &request.password)
-        .await?;
+        .await
+    {
+        Ok(user) => user,
+        Err(e) => {
+            if let Some(rate_limiter) = rate_limiter
+                && is_failed_login(&e)
+            {
+                rate_limiter.record_failure(&request.email, client_ip).await;
+            }
+            return Err(e.into());
+        }
+    };
+    if let Some(rate_limiter) = rate_limiter {
+        rate_limiter.reset(&request.email).await;
+    }
 
     let current_user = UserProfile::from(user.clone());
+    if let Some(token_service) = token_service {
+        return Ok(Json(AuthResponse {
+            id: user.id.to_string(),
+            email: user.email,
+            token: Some(issue_tokens(&app_state, token_service, &current_user).await?),
+        }));
+    }
 
-    session
-        .insert(SESSION_USER_KEY, &current_user)
-        .await
-        .map_err(|_| {
-            ApiError::new(
-                "failed_to_create_session_error".to_string(),
-                ErrorKind::InternalServerError,
-            )
-        })?;
+    start_user_session(&app_state, &session, &headers, client_address, &current_user).await?;
+    if app_state.single_active_session {
+        supersede_other_sessions(&app_state, &session, &user.id).await;
+    }
 
     Ok(Json(AuthResponse {
         id: user.id.to_string(),
         email: user.email,
+        token: None,
     }))
 }
 
@@ -150,36 +579,372 @@ pub async fn login(
     ),
     operation_id = "logout"
 )]
-pub async fn logout(session: Session) -> ApiResult<()> {
+pub async fn logout(State(app_state): State<Arc<AppState>>, session: Session) -> ApiResult<()> {
+    end_current_session(&app_state, &session).await?;
+    Ok(Json(()))
+}
+
+async fn end_current_session(app_state: &AppState, session: &Session) -> Result<(), ApiError> {
+    if let Some(session_id) = session.id() {
+        let removed = app_state
+            .session_service
+            .remove_session(&session_id.to_string())
+            .await;
+        if let Err(e) = removed {
+            tracing::warn!("Failed to remove session metadata: {}", e);
+        }
+    }
+
     session.flush().await.map_err(|_| {
         ApiError::new(
             "failed_to_logout_error".to_string(),
             ErrorKind::InternalServerError,
         )
-    })?;
+    })
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/logout-all",
+    description = "Logout the current user from every session, on all browsers and devices, including this one. Also revokes the user's refresh tokens, ending every token session.",
+    responses(
+        (status = 200, description = "Logout successfully"),
+        (status = 401, description = "Unauthorized - invalid, missing or superseded session", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "logout_all"
+)]
+pub async fn logout_all(
+    State(app_state): State<Arc<AppState>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    session: Session,
+) -> ApiResult<()> {
+    // Token clients hold no cookie session; their refresh tokens are what
+    // keeps them signed in.
+    app_state
+        .auth_service
+        .revoke_tokens(&current_user.id, &[TokenKind::Refresh])
+        .await?;
+    let sessions = app_state
+        .session_service
+        .list_sessions(&current_user.id)
+        .await?;
+    end_other_sessions(&app_state, &session, &sessions).await?;
+    end_current_session(&app_state, &session).await?;
+    Ok(Json(()))
+}
+
+async fn end_other_sessions(
+    app_state: &AppState,
+    session: &Session,
+    sessions: &[SessionMetadata],
+) -> Result<(), ApiError> {
+    let current_session_id = session.id().map(|id| id.to_string());
+    for other in sessions
+        .iter()
+        .filter(|s| Some(&s.id) != current_session_id.as_ref())
+    {
+        // A session left in the store would stay logged in, so this fails
+        // the request rather than reporting a partial logout as success.
+        delete_session(&app_state.session_store, &other.id)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to delete session: {}", e);
+                ApiError::new(
+                    "failed_to_logout_error".to_string(),
+                    ErrorKind::InternalServerError,
+                )
+            })?;
+        if let Err(e) = app_state.session_service.remove_session(&other.id).await {
+            tracing::warn!("Failed to remove session metadata: {}", e);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct DeleteAccountRequest {
+    #[validate(length(min = 1, message = "current_password_required"))]
+    #[schema(example = "currentPassword123!")]
+    pub current_password: String,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    delete,
+    path = "/auth/account",
+    description = "Delete the current authenticated user's account, revoke its tokens and log out every session. Requires the current password. The email stays reserved for a grace period before it can be registered again.",
+    request_body = DeleteAccountRequest,
+    responses(
+        (status = 200, description = "Account deleted"),
+        (status = 401, description = "Invalid current password or unauthorized", body = ApiError),
+        (status = 403, description = "Re-authentication required - the session last proved the password too long ago", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "delete_account"
+)]
+pub async fn delete_account(
+    State(app_state): State<Arc<AppState>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    source: AuthSource,
+    session: Session,
+    ValidatedJson(request): ValidatedJson<DeleteAccountRequest>,
+) -> ApiResult<()> {
+    require_recent_auth(
+        &session,
+        source,
+        app_state.reauth_max_age,
+        app_state.clock.as_ref(),
+    )
+    .await?;
+
+    let sessions = app_state
+        .session_service
+        .list_sessions(&current_user.id)
+        .await?;
+    app_state
+        .auth_service
+        .delete_account(&current_user.id, &request.current_password)
+        .await?;
+    end_other_sessions(&app_state, &session, &sessions).await?;
+    end_current_session(&app_state, &session).await?;
+    Ok(Json(()))
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct SessionResponse {
+    /// Opaque handle for revoking the session. The session id itself is
+    /// the cookie credential and is never returned.
+    #[schema(example = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08")]
+    pub id: String,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTimeUtc,
+    /// When the session last signed in.
+    #[schema(value_type = String, format = DateTime)]
+    pub last_seen_at: DateTimeUtc,
+    #[schema(example = "Mozilla/5.0 (X11; Linux x86_64)")]
+    pub user_agent: Option<String>,
+    #[schema(example = "203.0.113.7")]
+    pub ip_address: Option<String>,
+    /// Whether this is the session making the request.
+    pub current: bool,
+}
+
+impl SessionResponse {
+    fn new(metadata: SessionMetadata, current_session_id: Option<&str>) -> Self {
+        Self {
+            id: hash_token(&metadata.id),
+            current: current_session_id == Some(metadata.id.as_str()),
+            created_at: metadata.created_at,
+            last_seen_at: metadata.last_seen_at,
+            user_agent: metadata.user_agent,
+            ip_address: metadata.ip_address,
+        }
+    }
+}
+
+// Sessions whose store entry has expired are dropped from the metadata as
+// they are found. A store error keeps the session listed.
+async fn active_sessions(
+    app_state: &AppState,
+    user_id: &str,
+) -> Result<Vec<SessionMetadata>, ApiError> {
+    let sessions = app_state.session_service.list_sessions(user_id).await?;
+    let mut active = Vec::with_capacity(sessions.len());
+    for metadata in sessions {
+        let stored = match metadata.id.parse::<Id>() {
+            Ok(id) => app_state.session_store.load(&id).await.map(|r| r.is_some()),
+            Err(_) => Ok(false),
+        };
+        match stored {
+            Ok(false) => {
+                if let Err(e) = app_state.session_service.remove_session(&metadata.id).await {
+                    tracing::warn!("Failed to remove expired session metadata: {}", e);
+                }
+            }
+            Ok(true) => active.push(metadata),
+            Err(e) => {
+                tracing::warn!("Failed to check session: {}", e);
+                active.push(metadata);
+            }
+        }
+    }
+    Ok(active)
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    get,
+    path = "/auth/sessions",
+    description = "List the current user's active sessions with where and when each signed in.",
+    responses(
+        (status = 200, description = "Active sessions", body = Vec<SessionResponse>),
+        (status = 401, description = "Unauthorized - invalid, missing or superseded session", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "list_sessions"
+)]
+pub async fn list_sessions(
+    State(app_state): State<Arc<AppState>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    session: Session,
+) -> ApiResult<Vec<SessionResponse>> {
+    let current_session_id = session.id().map(|id| id.to_string());
+    let sessions = active_sessions(&app_state, &current_user.id)
+        .await?
+        .into_iter()
+        .map(|metadata| SessionResponse::new(metadata, current_session_id.as_deref()))
+        .collect();
+    Ok(Json(sessions))
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    delete,
+    path = "/auth/sessions/{id}",
+    description = "Revoke one of the current user's sessions, logging that browser or device out. Revoking the current session is the same as logout.",
+    params(("id" = String, Path, description = "Session handle from the session list")),
+    responses(
+        (status = 200, description = "Session revoked"),
+        (status = 401, description = "Unauthorized - invalid, missing or superseded session", body = ApiError),
+        (status = 404, description = "No such session for this user", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "revoke_session"
+)]
+pub async fn revoke_session(
+    State(app_state): State<Arc<AppState>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    Path(handle): Path<String>,
+    session: Session,
+) -> ApiResult<()> {
+    let sessions = app_state
+        .session_service
+        .list_sessions(&current_user.id)
+        .await?;
+    let Some(target) = sessions.into_iter().find(|s| hash_token(&s.id) == handle) else {
+        return Err(DomainError::NotFoundError.into());
+    };
 
+    if session.id().map(|id| id.to_string()) == Some(target.id.clone()) {
+        end_current_session(&app_state, &session).await?;
+        return Ok(Json(()));
+    }
+    delete_session(&app_state.session_store, &target.id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to delete session: {}", e);
+            ApiError::new(
+                "failed_to_revoke_session_error".to_string(),
+                ErrorKind::InternalServerError,
+            )
+        })?;
+    if let Err(e) = app_state.session_service.remove_session(&target.id).await {
+        tracing::warn!("Failed to remove session metadata: {}", e);
+    }
     Ok(Json(()))
 }
 
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ProfileResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    #[schema(example = "john.doe@example.com")]
+    pub email: String,
+    #[schema(example = "en-US")]
+    pub locale: Option<String>,
+    #[schema(example = "Europe/Paris")]
+    pub timezone: Option<String>,
+    pub email_verified: bool,
+}
+
+impl From<UserProfile> for ProfileResponse {
+    fn from(value: UserProfile) -> Self {
+        Self {
+            id: value.id,
+            email: value.email,
+            locale: value.locale,
+            timezone: value.timezone,
+            email_verified: value.email_verified,
+        }
+    }
+}
+
 #[utoipa::path(
     tag = AUTH_TAG,
     get,
     path = "/auth/profile",
     description = "Retrieve the current authenticated user's profile information. Requires a valid user session.",
     responses(
-        (status = 200, description = "User profile information", body = AuthResponse),
-        (status = 401, description = "Unauthorized - invalid or missing session", body = ApiError),
+        (status = 200, description = "User profile information", body = ProfileResponse),
+        (status = 401, description = "Unauthorized - invalid, missing or superseded session", body = ApiError),
         (status = 500, description = "Internal server error", body = ApiError)
     ),
     operation_id = "get_profile"
 )]
 pub async fn get_profile(
     AuthenticatedUser(current_user): AuthenticatedUser,
-) -> ApiResult<AuthResponse> {
-    Ok(Json(AuthResponse {
-        id: current_user.id,
-        email: current_user.email,
-    }))
+) -> ApiResult<ProfileResponse> {
+    Ok(Json(ProfileResponse::from(current_user)))
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct UpdateProfileRequest {
+    #[validate(custom(function = "validate_locale"))]
+    #[schema(example = "en-US")]
+    pub locale: Option<String>,
+    #[validate(custom(function = "validate_timezone"))]
+    #[schema(example = "Europe/Paris")]
+    pub timezone: Option<String>,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    put,
+    path = "/auth/profile",
+    description = "Update the current authenticated user's locale (BCP-47 language tag) and timezone (IANA time zone name). Omitted fields are cleared.",
+    request_body = UpdateProfileRequest,
+    responses(
+        (status = 200, description = "Profile updated successfully", body = ProfileResponse),
+        (status = 400, description = "Validation error - invalid locale or timezone", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid, missing or superseded session", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "update_profile"
+)]
+pub async fn update_profile(
+    State(app_state): State<Arc<AppState>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    session: Session,
+    ValidatedJson(request): ValidatedJson<UpdateProfileRequest>,
+) -> ApiResult<ProfileResponse> {
+    let user = app_state
+        .auth_service
+        .update_preferences(&current_user.id, request.locale, request.timezone)
+        .await?;
+
+    let profile = UserProfile::from(user);
+    // Only a session already signed in as this user is refreshed, so a
+    // bearer request never turns the session into a cookie login.
+    let session_user = session
+        .get::<UserProfile>(SESSION_USER_KEY)
+        .await
+        .ok()
+        .flatten();
+    if session_user.is_some_and(|session_user| session_user.id == profile.id) {
+        session
+            .insert(SESSION_USER_KEY, &profile)
+            .await
+            .map_err(|_| {
+                ApiError::new(
+                    "failed_to_update_session_error".to_string(),
+                    ErrorKind::InternalServerError,
+                )
+            })?;
+    }
+
+    Ok(Json(ProfileResponse::from(profile)))
 }
 
 #[derive(Deserialize, Debug, ToSchema, validator::Validate)]
@@ -196,12 +961,13 @@ pub struct ChangePasswordRequest {
     tag = AUTH_TAG,
     put,
     path = "/auth/change-password",
-    description = "Change the current authenticated user's password. Requires the current password for verification and a new password that meets security requirements.",
+    description = "Change the current authenticated user's password. Requires the current password for verification and a new password that meets security requirements. Revokes the user's refresh tokens, so token clients sign in again.",
     request_body = ChangePasswordRequest,
     responses(
         (status = 200, description = "Password changed successfully", body = AuthResponse),
-        (status = 400, description = "Validation error - check password length requirements", body = ApiError),
+        (status = 400, description = "Validation error - check password length requirements, or the new password appears in a known breach", body = ApiError),
         (status = 401, description = "Invalid current password or unauthorized", body = ApiError),
+        (status = 403, description = "Re-authentication required - the session last proved the password too long ago", body = ApiError),
         (status = 500, description = "Internal server error", body = ApiError)
     ),
     operation_id = "change_password"
@@ -209,15 +975,560 @@ pub struct ChangePasswordRequest {
 pub async fn change_password(
     State(app_state): State<Arc<AppState>>,
     AuthenticatedUser(current_user): AuthenticatedUser,
+    source: AuthSource,
+    session: Session,
     ValidatedJson(request): ValidatedJson<ChangePasswordRequest>,
 ) -> ApiResult<AuthResponse> {
+    require_recent_auth(
+        &session,
+        source,
+        app_state.reauth_max_age,
+        app_state.clock.as_ref(),
+    )
+    .await?;
+
     let user = app_state
         .auth_service
         .change_password(&current_user.id, &request.current_password, &request.new_password)
         .await?;
+    if source == AuthSource::Session {
+        mark_authenticated(&session, app_state.clock.as_ref()).await?;
+    }
+
+    Ok(Json(AuthResponse {
+        id: user.id.to_string(),
+        email: user.email,
+        token: None,
+    }))
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct ForgotPasswordRequest {
+    #[serde(deserialize_with = "normalize_email")]
+    #[validate(
+        email(message = "invalid_email_format"),
+        length(max = MAX_EMAIL_LENGTH, message = "email_too_long")
+    )]
+    #[schema(example = "john.doe@example.com")]
+    pub email: String,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/forgot-password",
+    description = "Request a password reset token for an email address. Responds the same whether or not the email is registered; when it is, a single-use token valid for 30 minutes is issued and any earlier token is revoked.",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 200, description = "Reset requested"),
+        (status = 400, description = "Validation error - check email format", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "forgot_password"
+)]
+pub async fn forgot_password(
+    State(app_state): State<Arc<AppState>>,
+    ValidatedJson(request): ValidatedJson<ForgotPasswordRequest>,
+) -> ApiResult<()> {
+    app_state
+        .auth_service
+        .request_password_reset(&request.email)
+        .await?;
+    Ok(Json(()))
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct MagicLinkRequest {
+    #[serde(deserialize_with = "normalize_email")]
+    #[validate(
+        email(message = "invalid_email_format"),
+        length(max = MAX_EMAIL_LENGTH, message = "email_too_long")
+    )]
+    #[schema(example = "john.doe@example.com")]
+    pub email: String,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/magic-link",
+    description = "Request a passwordless login link for an email address. Responds the same whether or not the email is registered; when it is, a single-use link valid for 10 minutes is issued and any earlier link is revoked.",
+    request_body = MagicLinkRequest,
+    responses(
+        (status = 200, description = "Login link requested"),
+        (status = 400, description = "Validation error - check email format", body = ApiError),
+        (status = 429, description = "Too many links requested for this email; see Retry-After", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "request_magic_link"
+)]
+pub async fn request_magic_link(
+    State(app_state): State<Arc<AppState>>,
+    ValidatedJson(request): ValidatedJson<MagicLinkRequest>,
+) -> ApiResult<()> {
+    if let Some(rate_limiter) = app_state.magic_link_rate_limiter.as_deref()
+        && let Some(retry_after) = rate_limiter.acquire(&request.email).await
+    {
+        return Err(ApiError::new(
+            "too_many_magic_link_requests_error".to_string(),
+            ErrorKind::TooManyRequests,
+        )
+        .with_retry_after(retry_after.as_secs().max(1)));
+    }
+
+    app_state
+        .auth_service
+        .request_magic_link(&request.email)
+        .await?;
+    Ok(Json(()))
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MagicLinkVerifyQuery {
+    /// The token from the login link.
+    pub token: Option<String>,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    get,
+    path = "/auth/magic-link/verify",
+    description = "Log in with a login link token and create a user session. Each link works once; following it also verifies the email.",
+    params(MagicLinkVerifyQuery),
+    responses(
+        (status = 200, description = "Login successfully", body = AuthResponse),
+        (status = 400, description = "Missing token", body = ApiError),
+        (status = 401, description = "The link is invalid, expired or already used", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "verify_magic_link"
+)]
+pub async fn verify_magic_link(
+    State(app_state): State<Arc<AppState>>,
+    ConnectInfo(client_address): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    session: Session,
+    Query(query): Query<MagicLinkVerifyQuery>,
+) -> ApiResult<AuthResponse> {
+    let Some(token) = query.token.filter(|token| !token.is_empty()) else {
+        return Err(ApiError::new(
+            "token_required".to_string(),
+            ErrorKind::BadRequest,
+        ));
+    };
+
+    let user = app_state.auth_service.login_with_magic_link(&token).await?;
+
+    let current_user = UserProfile::from(user.clone());
+    start_user_session(&app_state, &session, &headers, client_address, &current_user).await?;
+    if app_state.single_active_session {
+        supersede_other_sessions(&app_state, &session, &user.id).await;
+    }
+
+    Ok(Json(AuthResponse {
+        id: user.id.to_string(),
+        email: user.email,
+        token: None,
+    }))
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct ResetPasswordRequest {
+    #[validate(length(min = 1, message = "token_required"))]
+    pub token: String,
+    #[validate(length(min = 8, message = "new_password_must_be_at_least_8_characters"))]
+    #[schema(example = "newSecurePassword456!")]
+    pub new_password: String,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/reset-password",
+    description = "Set a new password using a token from /auth/forgot-password. The token is consumed on success and cannot be reused. Revokes the user's refresh tokens.",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 200, description = "Password reset successfully", body = AuthResponse),
+        (status = 400, description = "Validation error - the token is invalid, used or expired, or the new password is too short or appears in a known breach", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "reset_password"
+)]
+pub async fn reset_password(
+    State(app_state): State<Arc<AppState>>,
+    ValidatedJson(request): ValidatedJson<ResetPasswordRequest>,
+) -> ApiResult<AuthResponse> {
+    let user = app_state
+        .auth_service
+        .reset_password(&request.token, &request.new_password)
+        .await?;
 
     Ok(Json(AuthResponse {
         id: user.id.to_string(),
         email: user.email,
+        token: None,
     }))
 }
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct VerifyEmailRequest {
+    #[validate(length(min = 1, message = "token_required"))]
+    pub token: String,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/verify-email",
+    description = "Confirm the email address of an account using the verification token issued at registration. The token is consumed on success and cannot be reused.",
+    request_body = VerifyEmailRequest,
+    responses(
+        (status = 200, description = "Email verified successfully", body = ProfileResponse),
+        (status = 400, description = "Validation error - the token is invalid, used or expired", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "verify_email"
+)]
+pub async fn verify_email(
+    State(app_state): State<Arc<AppState>>,
+    session: Session,
+    ValidatedJson(request): ValidatedJson<VerifyEmailRequest>,
+) -> ApiResult<ProfileResponse> {
+    let user = app_state.auth_service.verify_email(&request.token).await?;
+    let profile = UserProfile::from(user);
+    refresh_session_profile(&session, &profile).await;
+    Ok(Json(ProfileResponse::from(profile)))
+}
+
+// Emailed links are often opened while signed in; keep that session's cached
+// profile in step. A failure only leaves it stale until the next login.
+async fn refresh_session_profile(session: &Session, profile: &UserProfile) {
+    let signed_in = session
+        .get::<UserProfile>(SESSION_USER_KEY)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|current_user| current_user.id == profile.id);
+    if signed_in && let Err(e) = session.insert(SESSION_USER_KEY, profile).await {
+        tracing::warn!("Failed to refresh the session profile: {}", e);
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct ChangeEmailRequest {
+    #[serde(deserialize_with = "normalize_email")]
+    #[validate(
+        email(message = "invalid_email_format"),
+        length(max = MAX_EMAIL_LENGTH, message = "email_too_long")
+    )]
+    #[schema(example = "jane.doe@example.com")]
+    pub new_email: String,
+    #[validate(length(min = 1, message = "current_password_required"))]
+    #[schema(example = "currentPassword123!")]
+    pub current_password: String,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    put,
+    path = "/auth/email",
+    description = "Request a change of the current authenticated user's email. Requires the current password. A single-use confirmation token valid for 24 hours is sent to the new address; the email changes only once it is confirmed, and any earlier pending change is revoked.",
+    request_body = ChangeEmailRequest,
+    responses(
+        (status = 200, description = "Confirmation sent to the new email"),
+        (status = 400, description = "Validation error - check email format, or the new email is the current one", body = ApiError),
+        (status = 401, description = "Invalid current password or unauthorized", body = ApiError),
+        (status = 403, description = "Re-authentication required - the session last proved the password too long ago", body = ApiError),
+        (status = 409, description = "The new email belongs to another account", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "change_email"
+)]
+pub async fn change_email(
+    State(app_state): State<Arc<AppState>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    source: AuthSource,
+    session: Session,
+    ValidatedJson(request): ValidatedJson<ChangeEmailRequest>,
+) -> ApiResult<()> {
+    require_recent_auth(
+        &session,
+        source,
+        app_state.reauth_max_age,
+        app_state.clock.as_ref(),
+    )
+    .await?;
+
+    app_state
+        .auth_service
+        .request_email_change(&current_user.id, &request.current_password, &request.new_email)
+        .await?;
+    Ok(Json(()))
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct ConfirmEmailChangeRequest {
+    #[validate(length(min = 1, message = "token_required"))]
+    pub token: String,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/email/confirm",
+    description = "Confirm an email change with the token sent to the new address. The account's email is replaced and marked verified.",
+    request_body = ConfirmEmailChangeRequest,
+    responses(
+        (status = 200, description = "Email changed", body = ProfileResponse),
+        (status = 400, description = "Token is invalid, expired or already used", body = ApiError),
+        (status = 409, description = "The new email was taken by another account meanwhile", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "confirm_email_change"
+)]
+pub async fn confirm_email_change(
+    State(app_state): State<Arc<AppState>>,
+    session: Session,
+    ValidatedJson(request): ValidatedJson<ConfirmEmailChangeRequest>,
+) -> ApiResult<ProfileResponse> {
+    let user = app_state
+        .auth_service
+        .confirm_email_change(&request.token)
+        .await?;
+    let profile = UserProfile::from(user);
+    refresh_session_profile(&session, &profile).await;
+    Ok(Json(ProfileResponse::from(profile)))
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct RefreshTokenRequest {
+    #[validate(length(min = 1, message = "refresh_token_required"))]
+    pub refresh_token: String,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/refresh",
+    description = "Exchange a refresh token for a new access token and refresh token. The presented refresh token is revoked; presenting it again revokes every token descended from the same login.",
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "New token pair", body = TokenResponse),
+        (status = 400, description = "Validation error, or token auth is disabled", body = ApiError),
+        (status = 401, description = "Refresh token is invalid, expired or already used", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "refresh_token"
+)]
+pub async fn refresh_token(
+    State(app_state): State<Arc<AppState>>,
+    ValidatedJson(request): ValidatedJson<RefreshTokenRequest>,
+) -> ApiResult<TokenResponse> {
+    let token_service = require_token_service(&app_state)?;
+    let (user, refresh_token) = app_state
+        .auth_service
+        .rotate_refresh_token(&request.refresh_token)
+        .await?;
+
+    let current_user = UserProfile::from(user);
+    Ok(Json(token_response(token_service, &current_user, refresh_token)?))
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RevocableTokenType {
+    PasswordReset,
+    EmailVerification,
+    Refresh,
+    MagicLink,
+    EmailChange,
+    All,
+}
+
+impl RevocableTokenType {
+    fn kinds(self) -> Vec<TokenKind> {
+        match self {
+            Self::PasswordReset => vec![TokenKind::PasswordReset],
+            Self::EmailVerification => vec![TokenKind::EmailVerification],
+            Self::Refresh => vec![TokenKind::Refresh],
+            Self::MagicLink => vec![TokenKind::MagicLink],
+            Self::EmailChange => vec![TokenKind::EmailChange],
+            Self::All => TokenKind::ALL.to_vec(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, ToSchema, validator::Validate)]
+pub struct RevokeTokensRequest {
+    #[serde(rename = "type")]
+    pub token_type: RevocableTokenType,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    post,
+    path = "/auth/tokens/revoke",
+    description = "Revoke every outstanding token of a type (password_reset, email_verification, refresh, magic_link, email_change or all) for the current authenticated user. Repeating the call is harmless.",
+    request_body = RevokeTokensRequest,
+    responses(
+        (status = 200, description = "Tokens revoked"),
+        (status = 400, description = "Invalid token type", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid, missing or superseded session", body = ApiError),
+        (status = 403, description = "Re-authentication required - the session last proved the password too long ago", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "revoke_tokens"
+)]
+pub async fn revoke_tokens(
+    State(app_state): State<Arc<AppState>>,
+    AuthenticatedUser(current_user): AuthenticatedUser,
+    source: AuthSource,
+    session: Session,
+    ValidatedJson(request): ValidatedJson<RevokeTokensRequest>,
+) -> ApiResult<()> {
+    require_recent_auth(
+        &session,
+        source,
+        app_state.reauth_max_age,
+        app_state.clock.as_ref(),
+    )
+    .await?;
+
+    app_state
+        .auth_service
+        .revoke_tokens(&current_user.id, &request.token_type.kinds())
+        .await?;
+    Ok(Json(()))
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    get,
+    path = "/auth/oauth/{provider}",
+    description = "Start signing in with an external identity provider (currently google). Redirects the browser to the provider, which sends it back to the callback.",
+    params(("provider" = String, Path, description = "Identity provider name", example = "google")),
+    responses(
+        (status = 303, description = "Redirect to the provider's sign-in page"),
+        (status = 404, description = "The provider is unknown or not configured", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "oauth_authorize"
+)]
+pub async fn oauth_authorize(
+    State(app_state): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+    session: Session,
+) -> Result<Redirect, ApiError> {
+    // The state ties the callback to this browser, so a forged callback
+    // cannot sign the victim into the attacker's account.
+    let state = SecretToken::generate();
+    let authorization_url = app_state
+        .auth_service
+        .oauth_authorization_url(&provider, &state.0)?;
+    session
+        .insert(SESSION_OAUTH_STATE_KEY, state.hash())
+        .await
+        .map_err(|_| {
+            ApiError::new(
+                "failed_to_create_session_error".to_string(),
+                ErrorKind::InternalServerError,
+            )
+        })?;
+    Ok(Redirect::to(&authorization_url))
+}
+
+#[derive(Deserialize, Debug, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OAuthCallbackQuery {
+    /// Authorization code, absent when the user declined.
+    pub code: Option<String>,
+    pub state: Option<String>,
+    /// Set by the provider when the sign-in did not complete.
+    pub error: Option<String>,
+}
+
+#[utoipa::path(
+    tag = AUTH_TAG,
+    get,
+    path = "/auth/oauth/{provider}/callback",
+    description = "Complete signing in with an external identity provider. Logs into the account linked to the provider identity; on first use links the account with the same verified email, or creates a passwordless one. Redirects to the configured success page.",
+    params(
+        ("provider" = String, Path, description = "Identity provider name", example = "google"),
+        OAuthCallbackQuery
+    ),
+    responses(
+        (status = 303, description = "Signed in, redirect to the success page"),
+        (status = 400, description = "Missing or mismatched state, the provider has not verified the email, or an unverified account holds it", body = ApiError),
+        (status = 401, description = "The provider rejected the sign-in", body = ApiError),
+        (status = 404, description = "The provider is unknown or not configured", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    ),
+    operation_id = "oauth_callback"
+)]
+pub async fn oauth_callback(
+    State(app_state): State<Arc<AppState>>,
+    ConnectInfo(client_address): ConnectInfo<SocketAddr>,
+    Path(provider): Path<String>,
+    headers: HeaderMap,
+    session: Session,
+    Query(query): Query<OAuthCallbackQuery>,
+) -> Result<Redirect, ApiError> {
+    let expected_state: Option<String> = session
+        .remove(SESSION_OAUTH_STATE_KEY)
+        .await
+        .ok()
+        .flatten();
+    let state_matches = match (&expected_state, &query.state) {
+        (Some(expected), Some(state)) => *expected == hash_token(state),
+        _ => false,
+    };
+    if !state_matches {
+        return Err(ApiError::new(
+            "invalid_oauth_state_error".to_string(),
+            ErrorKind::BadRequest,
+        ));
+    }
+    let Some(code) = query.code.filter(|_| query.error.is_none()) else {
+        return Err(DomainError::AuthenticationFailed.into());
+    };
+
+    let user = app_state.auth_service.login_with_oauth(&provider, &code).await?;
+
+    let current_user = UserProfile::from(user.clone());
+    start_user_session(&app_state, &session, &headers, client_address, &current_user).await?;
+    if app_state.single_active_session {
+        supersede_other_sessions(&app_state, &session, &user.id).await;
+    }
+
+    Ok(Redirect::to(&app_state.oauth_success_redirect))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn login_email_is_trimmed_and_lowercased() {
+        let request: LoginRequest = serde_json::from_value(serde_json::json!({
+            "email": "  John@Example.com ",
+            "password": "securePassword123!"
+        }))
+        .unwrap();
+
+        assert_eq!(request.email, "john@example.com");
+        assert!(request.validate().is_ok());
+    }
+
+    #[test]
+    fn register_email_is_trimmed_and_lowercased() {
+        let request: RegisterRequest = serde_json::from_value(serde_json::json!({
+            "email": "  John@Example.com ",
+            "password": "securePassword123!"
+        }))
+        .unwrap();
+
+        assert_eq!(request.email, "john@example.com");
+        assert!(request.validate().is_ok());
+    }
+}
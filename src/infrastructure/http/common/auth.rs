@@ -13,53 +13,345 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use crate::domain::clock::Clock;
 use crate::domain::user::UserProfile;
+use crate::infrastructure::app_state::AppState;
 use crate::infrastructure::http::error_handler::{ApiError, ErrorKind};
 use axum::extract::FromRequestParts;
+use axum::http::header::{AUTHORIZATION, COOKIE};
 use axum::http::request::Parts;
 use axum::RequestPartsExt;
-use tower_sessions::Session;
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+use tower_sessions::session::{Error as SessionError, Id};
+use tower_sessions::{session_store, Session, SessionStore};
 
 pub const SESSION_USER_KEY: &str = "user";
+pub const SESSION_SUPERSEDED_KEY: &str = "superseded";
+pub const SESSION_LAST_AUTHENTICATED_AT_KEY: &str = "last_authenticated_at";
 
+fn has_cookie(parts: &Parts, cookie_name: &str) -> bool {
+    parts
+        .headers
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .any(|(name, value)| name == cookie_name && !value.is_empty())
+}
+
+/// The signed-in user, taken from an `Authorization: Bearer` access token
+/// when token authentication is enabled and one is sent, else from the
+/// session.
 pub struct AuthenticatedUser(pub UserProfile);
 
-#[async_trait::async_trait]
-impl<S> FromRequestParts<S> for AuthenticatedUser
-where
-    S: Send + Sync,
-{
+/// Authenticates with an `Authorization: Bearer` access token only,
+/// resolving to the same profile as `AuthenticatedUser`.
+pub struct BearerAuthenticatedUser(pub UserProfile);
+
+/// What `AuthenticatedUser` takes the identity from on this request. Only a
+/// session-authenticated request may write to the session; a bearer request
+/// must not turn into a cookie login.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthSource {
+    Session,
+    Bearer,
+}
+
+impl AuthSource {
+    fn of(parts: &Parts, state: &AppState) -> Self {
+        if state.token_service.is_some() && bearer_token(parts).is_some() {
+            AuthSource::Bearer
+        } else {
+            AuthSource::Session
+        }
+    }
+}
+
+fn bearer_token(parts: &Parts) -> Option<&str> {
+    let value = parts.headers.get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+impl FromRequestParts<Arc<AppState>> for AuthSource {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(AuthSource::of(parts, state))
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for BearerAuthenticatedUser {
     type Rejection = ApiError;
 
-    fn from_request_parts(
+    async fn from_request_parts(
         parts: &mut Parts,
-        _state: &S,
-    ) -> impl Future<Output=Result<Self, Self::Rejection>> + Send {
-        async move {
-            let session = parts.extract::<Session>().await.map_err(|_| {
-                ApiError::new(
-                    "internal_server_error".to_string(),
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        let (Some(token_service), Some(token)) = (&state.token_service, bearer_token(parts)) else {
+            return Err(ApiError::new(
+                "unauthenticated_error".to_string(),
+                ErrorKind::Unauthorized,
+            ));
+        };
+
+        match token_service.verify(token) {
+            Ok(current_user) => Ok(BearerAuthenticatedUser(current_user)),
+            Err(e) => {
+                tracing::debug!("Rejected access token: {}", e);
+                Err(ApiError::new(
+                    "invalid_token_error".to_string(),
+                    ErrorKind::Unauthorized,
+                ))
+            }
+        }
+    }
+}
+
+impl FromRequestParts<Arc<AppState>> for AuthenticatedUser {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<AppState>,
+    ) -> Result<Self, Self::Rejection> {
+        if AuthSource::of(parts, state) == AuthSource::Bearer {
+            let BearerAuthenticatedUser(current_user) =
+                BearerAuthenticatedUser::from_request_parts(parts, state).await?;
+            return Ok(AuthenticatedUser(current_user));
+        }
+
+        if let Some(cookie_name) = &state.required_session_cookie
+            && !has_cookie(parts, cookie_name)
+        {
+            return Err(ApiError::new(
+                "unauthenticated_error".to_string(),
+                ErrorKind::Unauthorized,
+            ));
+        }
+
+        let session = parts.extract::<Session>().await.map_err(|(_, message)| {
+            tracing::error!("Session layer is not configured: {}", message);
+            ApiError::new(
+                "session_unavailable_error".to_string(),
+                ErrorKind::InternalServerError,
+            )
+        })?;
+
+        let current_user = match session.get::<UserProfile>(SESSION_USER_KEY).await {
+            Ok(current_user) => current_user,
+            Err(e) if is_corrupt_session(&e) => {
+                discard_corrupt_session(&session, &e).await;
+                None
+            }
+            Err(e) => {
+                tracing::error!("Failed to load session: {:?}", e);
+                return Err(ApiError::new(
+                    "session_store_error".to_string(),
                     ErrorKind::InternalServerError,
-                )
-            })?;
+                ));
+            }
+        };
 
-            let current_user: UserProfile = session
-                .get(SESSION_USER_KEY)
-                .await
-                .map_err(|_| {
-                    ApiError::new(
-                        "internal_server_error".to_string(),
-                        ErrorKind::InternalServerError,
-                    )
-                })?
-                .ok_or_else(|| {
-                    ApiError::new(
-                        "unauthenticated_error".to_string(),
-                        ErrorKind::Unauthorized,
-                    )
-                })?;
-
-            Ok(AuthenticatedUser(current_user))
+        let Some(current_user) = current_user else {
+            return Err(rejection_for_anonymous(&session).await);
+        };
+
+        Ok(AuthenticatedUser(current_user))
+    }
+}
+
+/// Records that the session has just proven the password.
+pub async fn mark_authenticated(session: &Session, clock: &dyn Clock) -> Result<(), ApiError> {
+    session
+        .insert(SESSION_LAST_AUTHENTICATED_AT_KEY, clock.now().timestamp())
+        .await
+        .map_err(|_| {
+            ApiError::new(
+                "failed_to_create_session_error".to_string(),
+                ErrorKind::InternalServerError,
+            )
+        })
+}
+
+/// Guards sensitive mutations: the session must have proven the password
+/// within `max_age`. `None` disables the check. Bearer requests carry no such
+/// proof, so they are refused while the check is enabled.
+pub async fn require_recent_auth(
+    session: &Session,
+    source: AuthSource,
+    max_age: Option<Duration>,
+    clock: &dyn Clock,
+) -> Result<(), ApiError> {
+    let Some(max_age) = max_age else {
+        return Ok(());
+    };
+    // A cookie sent along with a bearer token does not vouch for the token.
+    if source != AuthSource::Session {
+        return Err(ApiError::new(
+            "reauth_required".to_string(),
+            ErrorKind::Forbidden,
+        ));
+    }
+    let last_authenticated_at = session
+        .get::<i64>(SESSION_LAST_AUTHENTICATED_AT_KEY)
+        .await
+        .ok()
+        .flatten();
+    let is_recent = last_authenticated_at.is_some_and(|timestamp| {
+        clock.now().timestamp().saturating_sub(timestamp) <= max_age.as_secs() as i64
+    });
+    if !is_recent {
+        return Err(ApiError::new(
+            "reauth_required".to_string(),
+            ErrorKind::Forbidden,
+        ));
+    }
+    Ok(())
+}
+
+async fn rejection_for_anonymous(session: &Session) -> ApiError {
+    if let Ok(Some(true)) = session.get::<bool>(SESSION_SUPERSEDED_KEY).await {
+        if let Err(e) = session.flush().await {
+            tracing::error!("Failed to flush superseded session: {:?}", e);
         }
+        return ApiError::new("session_superseded".to_string(), ErrorKind::Unauthorized);
+    }
+    ApiError::new(
+        "unauthenticated_error".to_string(),
+        ErrorKind::Unauthorized,
+    )
+}
+
+/// Logs a session out in place, keeping a marker so its next request is
+/// rejected with `session_superseded` instead of a plain 401.
+pub async fn supersede_session(
+    store: &impl SessionStore,
+    session_id: &str,
+) -> Result<(), session_store::Error> {
+    let Ok(id) = session_id.parse::<Id>() else {
+        return Ok(());
+    };
+    if let Some(mut record) = store.load(&id).await? {
+        record.data.remove(SESSION_USER_KEY);
+        record
+            .data
+            .insert(SESSION_SUPERSEDED_KEY.to_string(), serde_json::Value::Bool(true));
+        store.save(&record).await?;
+    }
+    Ok(())
+}
+
+/// Removes a session from the store so its cookie no longer authenticates.
+pub async fn delete_session(
+    store: &impl SessionStore,
+    session_id: &str,
+) -> Result<(), session_store::Error> {
+    let Ok(id) = session_id.parse::<Id>() else {
+        return Ok(());
+    };
+    store.delete(&id).await
+}
+
+fn is_corrupt_session(error: &SessionError) -> bool {
+    matches!(
+        error,
+        SessionError::SerdeJson(_) | SessionError::Store(session_store::Error::Decode(_))
+    )
+}
+
+async fn discard_corrupt_session(session: &Session, error: &SessionError) {
+    // Only a prefix of the id is logged, the full id is the session credential.
+    let session_id: String = session
+        .id()
+        .map(|id| id.to_string().chars().take(8).collect())
+        .unwrap_or_default();
+    tracing::warn!(
+        "Discarding session {}... with unreadable data: {}",
+        session_id,
+        error
+    );
+    if let Err(e) = session.flush().await {
+        tracing::error!("Failed to flush corrupt session: {:?}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::clock::FixedClock;
+    use chrono::{TimeZone, Utc};
+    use tower_sessions::MemoryStore;
+
+    const MAX_AGE: Option<Duration> = Some(Duration::from_secs(300));
+
+    fn fixed_clock() -> FixedClock {
+        let now = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        FixedClock::new(now.into())
+    }
+
+    async fn authenticated_session(clock: &FixedClock) -> Session {
+        let session = Session::new(None, Arc::new(MemoryStore::default()), None);
+        mark_authenticated(&session, clock).await.unwrap();
+        session
+    }
+
+    #[tokio::test]
+    async fn login_within_max_age_is_recent() {
+        let clock = fixed_clock();
+        let session = authenticated_session(&clock).await;
+        clock.advance(chrono::Duration::seconds(300));
+
+        assert!(
+            require_recent_auth(&session, AuthSource::Session, MAX_AGE, &clock)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn login_older_than_max_age_requires_reauth() {
+        let clock = fixed_clock();
+        let session = authenticated_session(&clock).await;
+        clock.advance(chrono::Duration::seconds(301));
+
+        let error = require_recent_auth(&session, AuthSource::Session, MAX_AGE, &clock)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.message, "reauth_required");
+    }
+
+    #[tokio::test]
+    async fn bearer_request_requires_reauth_despite_recent_session() {
+        let clock = fixed_clock();
+        let session = authenticated_session(&clock).await;
+
+        assert!(
+            require_recent_auth(&session, AuthSource::Bearer, MAX_AGE, &clock)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn no_max_age_disables_the_check() {
+        let clock = fixed_clock();
+        let session = Session::new(None, Arc::new(MemoryStore::default()), None);
+
+        assert!(
+            require_recent_auth(&session, AuthSource::Bearer, None, &clock)
+                .await
+                .is_ok()
+        );
     }
 }
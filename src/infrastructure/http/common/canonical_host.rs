@@ -0,0 +1,113 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::{env_flag, env_var};
+use crate::infrastructure::http::common::security_headers::{
+    is_https_request, trust_proxy_from_env,
+};
+use axum::extract::{Request, State};
+use axum::http::header::HOST;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Redirect, Response};
+use std::sync::Arc;
+
+// Probes hit the pod directly, by IP or an internal name.
+const EXCLUDED_PREFIXES: [&str; 1] = ["/health"];
+
+#[derive(Clone, Debug, Default)]
+pub struct CanonicalHostConfig {
+    pub host: Option<String>,
+    pub force_https: bool,
+    pub trust_proxy: bool,
+}
+
+impl CanonicalHostConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let host = env_var("CANONICAL_HOST").map(|host| host.to_lowercase());
+        if let Some(host) = &host
+            && (host.is_empty() || host.contains(['/', '?', '#', '@']))
+        {
+            anyhow::bail!(
+                "CANONICAL_HOST {:?} must be a bare host, optionally with a port",
+                host
+            );
+        }
+        Ok(Self {
+            host,
+            force_https: env_flag("CANONICAL_HTTPS", false)?,
+            trust_proxy: trust_proxy_from_env()?,
+        })
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.host.is_some() || self.force_https
+    }
+}
+
+/// Redirects (308) requests reaching a non-canonical host or plain HTTP to the
+/// canonical origin, so the session cookie is only ever set there.
+pub async fn canonical_host(
+    State(config): State<Arc<CanonicalHostConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if !config.is_enabled()
+        || EXCLUDED_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+    {
+        return next.run(request).await;
+    }
+
+    let request_host = request
+        .headers()
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| {
+            request
+                .uri()
+                .authority()
+                .map(|authority| authority.as_str())
+        })
+        .map(str::to_lowercase);
+    let Some(request_host) = request_host else {
+        return next.run(request).await;
+    };
+    let is_https = is_https_request(&request, config.trust_proxy);
+
+    let host_matches = config
+        .host
+        .as_ref()
+        .is_none_or(|host| *host == request_host);
+    let scheme_matches = !config.force_https || is_https;
+    if host_matches && scheme_matches {
+        return next.run(request).await;
+    }
+
+    let scheme = if config.force_https || is_https {
+        "https"
+    } else {
+        "http"
+    };
+    let host = config.host.as_deref().unwrap_or(&request_host);
+    let path_and_query = request
+        .uri()
+        .path_and_query()
+        .map(|value| value.as_str())
+        .unwrap_or("/");
+    Redirect::permanent(&format!("{}://{}{}", scheme, host, path_and_query)).into_response()
+}
@@ -0,0 +1,84 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::http::error_handler::JSON_CONTENT_TYPE;
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::http::header::CONTENT_TYPE;
+use axum::middleware::Next;
+use axum::response::Response;
+
+pub async fn json_charset(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    let is_bare_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"application/json"));
+    if is_bare_json {
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(JSON_CONTENT_TYPE));
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::http::error_handler::{ApiError, ErrorKind};
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::{Json, Router, middleware};
+    use tower::ServiceExt;
+
+    fn router() -> Router {
+        Router::new()
+            .route(
+                "/ok",
+                get(|| async { Json(serde_json::json!({ "ok": true })) }),
+            )
+            .route(
+                "/error",
+                get(|| async { ApiError::new("not_found_error".to_string(), ErrorKind::NotFound) }),
+            )
+            .layer(middleware::from_fn(json_charset))
+    }
+
+    async fn content_type(uri: &str) -> Option<HeaderValue> {
+        let request = axum::http::Request::builder()
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        let response = router().oneshot(request).await.unwrap();
+        response.headers().get(CONTENT_TYPE).cloned()
+    }
+
+    #[tokio::test]
+    async fn error_response_declares_utf8_charset() {
+        assert_eq!(
+            content_type("/error").await,
+            Some(HeaderValue::from_static("application/json; charset=utf-8"))
+        );
+    }
+
+    #[tokio::test]
+    async fn success_response_declares_utf8_charset() {
+        assert_eq!(
+            content_type("/ok").await,
+            Some(HeaderValue::from_static("application/json; charset=utf-8"))
+        );
+    }
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::env_var;
+use axum::http::header::{ACCEPT, CONTENT_TYPE};
+use axum::http::{HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+// Public routes (health, docs) are world-readable, authenticated routes only accept
+// the configured origins and allow credentials so the session cookie is sent.
+#[derive(Clone, Debug, Default)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<HeaderValue>,
+}
+
+impl CorsConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let allowed_origins = env_var("CORS_ALLOWED_ORIGINS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|origin| !origin.is_empty())
+            .map(|origin| {
+                HeaderValue::from_str(origin).map_err(|e| {
+                    anyhow::anyhow!("Invalid CORS_ALLOWED_ORIGINS entry {}: {}", origin, e)
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { allowed_origins })
+    }
+
+    pub fn public_layer(&self) -> CorsLayer {
+        CorsLayer::permissive()
+    }
+
+    pub fn authenticated_layer(&self) -> CorsLayer {
+        CorsLayer::new()
+            .allow_origin(AllowOrigin::list(self.allowed_origins.clone()))
+            .allow_credentials(true)
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+            .allow_headers([ACCEPT, CONTENT_TYPE])
+    }
+}
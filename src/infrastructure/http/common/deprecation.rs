@@ -0,0 +1,101 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use axum::extract::{MatchedPath, Request};
+use axum::http::header::LINK;
+use axum::http::{HeaderName, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::Response;
+use chrono::DateTime;
+use utoipa::openapi::path::Operation;
+use utoipa::openapi::{Deprecated, OpenApi};
+
+const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// A superseded endpoint. Listed routes answer with `Deprecation` (RFC 9745)
+/// and `Sunset` (RFC 8594) headers and are flagged deprecated in the spec.
+pub struct DeprecatedRoute {
+    pub method: Method,
+    /// Route template as registered, e.g. `/auth/profile`.
+    pub path: &'static str,
+    /// Unix timestamp from which the endpoint is deprecated.
+    pub deprecated_at: i64,
+    /// Unix timestamp after which the endpoint may be removed.
+    pub sunset_at: Option<i64>,
+    /// Where to migrate to, sent as a `successor-version` link.
+    pub successor: Option<&'static str>,
+}
+
+pub const DEPRECATED_ROUTES: &[DeprecatedRoute] = &[];
+
+fn find_deprecated_route(method: &Method, path: &str) -> Option<&'static DeprecatedRoute> {
+    DEPRECATED_ROUTES
+        .iter()
+        .find(|route| route.method == method && route.path == path)
+}
+
+fn http_date(timestamp: i64) -> Option<HeaderValue> {
+    let date = DateTime::from_timestamp(timestamp, 0)?;
+    HeaderValue::try_from(date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()).ok()
+}
+
+pub async fn deprecation_headers(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| find_deprecated_route(request.method(), path.as_str()));
+    let mut response = next.run(request).await;
+    let Some(route) = route else {
+        return response;
+    };
+
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::try_from(format!("@{}", route.deprecated_at)) {
+        headers.insert(DEPRECATION, value);
+    }
+    if let Some(value) = route.sunset_at.and_then(http_date) {
+        headers.insert(SUNSET, value);
+    }
+    if let Some(successor) = route.successor
+        && let Ok(value) =
+            HeaderValue::try_from(format!("<{}>; rel=\"successor-version\"", successor))
+    {
+        headers.append(LINK, value);
+    }
+    response
+}
+
+/// Flags every listed route as deprecated in the generated spec.
+pub fn mark_deprecated_operations(api: &mut OpenApi) {
+    for route in DEPRECATED_ROUTES {
+        let Some(item) = api.paths.paths.get_mut(route.path) else {
+            tracing::warn!("Deprecated route {} is not in the OpenAPI spec", route.path);
+            continue;
+        };
+        let operation: Option<&mut Operation> = match route.method {
+            Method::GET => item.get.as_mut(),
+            Method::PUT => item.put.as_mut(),
+            Method::POST => item.post.as_mut(),
+            Method::DELETE => item.delete.as_mut(),
+            Method::PATCH => item.patch.as_mut(),
+            _ => None,
+        };
+        if let Some(operation) = operation {
+            operation.deprecated = Some(Deprecated::True);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::{env_parse, env_var};
+use axum::extract::{MatchedPath, Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug, Default)]
+pub struct LatencyBudgetConfig {
+    /// `None` disables the warning for routes without an override.
+    pub default: Option<Duration>,
+    /// Keyed by `METHOD /route` or `/route`, the former taking precedence.
+    pub overrides: HashMap<String, Duration>,
+}
+
+impl LatencyBudgetConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let default_ms: u64 = env_parse("LATENCY_BUDGET_MS", 1000)?;
+        let overrides = env_var("LATENCY_BUDGET_OVERRIDES")
+            .map(|value| Self::parse_overrides(&value))
+            .transpose()?
+            .unwrap_or_default();
+        Ok(Self {
+            default: (default_ms > 0).then(|| Duration::from_millis(default_ms)),
+            overrides,
+        })
+    }
+
+    fn parse_overrides(value: &str) -> anyhow::Result<HashMap<String, Duration>> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (route, ms) = entry.rsplit_once('=').ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "LATENCY_BUDGET_OVERRIDES entry {:?} must be ROUTE=MILLISECONDS",
+                        entry
+                    )
+                })?;
+                let ms: u64 = ms.trim().parse().map_err(|e| {
+                    anyhow::anyhow!("Invalid LATENCY_BUDGET_OVERRIDES entry {:?}: {}", entry, e)
+                })?;
+                let route = match route.trim().split_once(' ') {
+                    Some((method, path)) => {
+                        format!("{} {}", method.to_uppercase(), path.trim())
+                    }
+                    None => route.trim().to_string(),
+                };
+                Ok((route, Duration::from_millis(ms)))
+            })
+            .collect()
+    }
+
+    fn budget_for(&self, method: &str, route: &str) -> Option<Duration> {
+        self.overrides
+            .get(&format!("{} {}", method, route))
+            .or_else(|| self.overrides.get(route))
+            .copied()
+            .or(self.default)
+            .filter(|budget| !budget.is_zero())
+    }
+}
+
+/// Copies the matched route template onto the response, so middleware
+/// outside the router can report the route rather than the raw path.
+pub async fn record_matched_path(request: Request, next: Next) -> Response {
+    let matched_path = request.extensions().get::<MatchedPath>().cloned();
+    let mut response = next.run(request).await;
+    if let Some(matched_path) = matched_path {
+        response.extensions_mut().insert(matched_path);
+    }
+    response
+}
+
+/// Logs a warning when a request takes longer than its route's budget.
+pub async fn latency_budget(
+    State(config): State<Arc<LatencyBudgetConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let started_at = Instant::now();
+    let response = next.run(request).await;
+    let elapsed = started_at.elapsed();
+
+    let route = response
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or(path.as_str(), MatchedPath::as_str);
+    if let Some(budget) = config.budget_for(method.as_str(), route)
+        && elapsed > budget
+    {
+        tracing::warn!(
+            method = %method,
+            route = %route,
+            status = response.status().as_u16(),
+            duration_ms = elapsed.as_millis() as u64,
+            budget_ms = budget.as_millis() as u64,
+            "Request exceeded its latency budget"
+        );
+    }
+    response
+}
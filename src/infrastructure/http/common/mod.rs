@@ -14,4 +14,14 @@
  * limitations under the License.
  */
 pub mod auth;
+pub mod canonical_host;
+pub mod content_type;
+pub mod cors;
+pub mod deprecation;
+pub mod latency_budget;
+pub mod request_id;
+pub mod security_headers;
+pub mod session_expiry;
+pub mod timeout;
+pub mod trailing_slash;
 pub mod validator;
@@ -0,0 +1,144 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::{env_flag, env_or};
+use axum::body::Body;
+use axum::http::{HeaderName, HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use tower_http::request_id::{MakeRequestId, RequestId};
+
+const TRACEPARENT_HEADER: &str = "traceparent";
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+#[derive(Clone, Debug)]
+pub struct RequestIdConfig {
+    pub header_name: HeaderName,
+    pub from_traceparent: bool,
+}
+
+impl RequestIdConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let header_name = env_or("REQUEST_ID_HEADER", "x-request-id");
+        Ok(Self {
+            header_name: HeaderName::try_from(header_name.as_str()).map_err(|e| {
+                anyhow::anyhow!("Invalid REQUEST_ID_HEADER environment variable: {}", e)
+            })?,
+            from_traceparent: env_flag("REQUEST_ID_FROM_TRACEPARENT", true)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MakeRequestIdFromTrace {
+    from_traceparent: bool,
+}
+
+impl MakeRequestIdFromTrace {
+    pub fn new(config: &RequestIdConfig) -> Self {
+        Self {
+            from_traceparent: config.from_traceparent,
+        }
+    }
+}
+
+impl MakeRequestId for MakeRequestIdFromTrace {
+    fn make_request_id<B>(&mut self, request: &Request<B>) -> Option<RequestId> {
+        let trace_id = self
+            .from_traceparent
+            .then(|| request.headers().get(TRACEPARENT_HEADER))
+            .flatten()
+            .and_then(|value| value.to_str().ok())
+            .and_then(trace_id_from_traceparent);
+        let request_id = trace_id.unwrap_or_else(|| uuid::Uuid::now_v7().to_string());
+        HeaderValue::from_str(&request_id).ok().map(RequestId::new)
+    }
+}
+
+// traceparent is `version-traceid-parentid-flags`, see https://www.w3.org/TR/trace-context/
+fn trace_id_from_traceparent(traceparent: &str) -> Option<String> {
+    let trace_id = traceparent.trim().split('-').nth(1)?;
+    let is_valid = trace_id.len() == 32
+        && trace_id.chars().all(|c| c.is_ascii_hexdigit())
+        && trace_id.chars().any(|c| c != '0');
+    is_valid.then(|| trace_id.to_lowercase())
+}
+
+/// Makes the id resolved by `SetRequestIdLayer` visible to everything the
+/// request awaits on the same task, so error bodies can carry it.
+pub async fn scope_request_id(request: Request<Body>, next: Next) -> Response {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .and_then(|id| id.header_value().to_str().ok())
+        .map(str::to_string);
+    match request_id {
+        Some(request_id) => {
+            CURRENT_REQUEST_ID
+                .scope(request_id, next.run(request))
+                .await
+        }
+        None => next.run(request).await,
+    }
+}
+
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(String::clone).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::http::error_handler::{ApiError, ErrorKind};
+    use axum::Router;
+    use axum::body::to_bytes;
+    use axum::middleware;
+    use axum::routing::get;
+    use tower::ServiceExt;
+    use tower_http::request_id::SetRequestIdLayer;
+
+    #[tokio::test]
+    async fn error_body_carries_the_request_id() {
+        let config = RequestIdConfig {
+            header_name: HeaderName::from_static("x-request-id"),
+            from_traceparent: false,
+        };
+        let router = Router::new()
+            .route(
+                "/missing",
+                get(|| async { ApiError::new("not_found_error".to_string(), ErrorKind::NotFound) }),
+            )
+            .layer(middleware::from_fn(scope_request_id))
+            .layer(SetRequestIdLayer::new(
+                config.header_name.clone(),
+                MakeRequestIdFromTrace::new(&config),
+            ));
+        let request = Request::builder()
+            .uri("/missing")
+            .header("x-request-id", "req-123")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["request_id"], "req-123");
+    }
+}
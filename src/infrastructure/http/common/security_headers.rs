@@ -0,0 +1,143 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::{env_flag, env_or, env_parse, env_var};
+use axum::extract::{Request, State};
+use axum::http::HeaderValue;
+use axum::http::header::{
+    CONTENT_SECURITY_POLICY, REFERRER_POLICY, STRICT_TRANSPORT_SECURITY, X_CONTENT_TYPE_OPTIONS,
+    X_FRAME_OPTIONS,
+};
+use axum::middleware::Next;
+use axum::response::Response;
+use std::sync::Arc;
+
+const FORWARDED_PROTO_HEADER: &str = "x-forwarded-proto";
+const DEFAULT_DOCS_CONTENT_SECURITY_POLICY: &str = "default-src 'self'; \
+    script-src 'self' 'unsafe-inline' https://cdn.jsdelivr.net; \
+    style-src 'self' 'unsafe-inline' https://cdn.jsdelivr.net https://fonts.googleapis.com; \
+    font-src 'self' data: https://fonts.gstatic.com https://fonts.scalar.com; \
+    img-src 'self' data: https:; \
+    connect-src 'self'; \
+    frame-ancestors 'none'";
+
+#[derive(Clone, Debug)]
+pub struct SecurityHeadersConfig {
+    pub enabled: bool,
+    pub frame_options: HeaderValue,
+    pub referrer_policy: HeaderValue,
+    pub hsts: HeaderValue,
+    pub trust_proxy: bool,
+    pub docs_content_security_policy: HeaderValue,
+}
+
+impl SecurityHeadersConfig {
+    pub fn from_env() -> anyhow::Result<Self> {
+        Ok(Self {
+            enabled: env_flag("SECURITY_HEADERS_ENABLED", true)?,
+            frame_options: header_from_env("X_FRAME_OPTIONS", "DENY")?,
+            referrer_policy: header_from_env("REFERRER_POLICY", "strict-origin-when-cross-origin")?,
+            hsts: hsts_from_env()?,
+            trust_proxy: trust_proxy_from_env()?,
+            docs_content_security_policy: header_from_env(
+                "DOCS_CONTENT_SECURITY_POLICY",
+                DEFAULT_DOCS_CONTENT_SECURITY_POLICY,
+            )?,
+        })
+    }
+}
+
+fn header_from_env(name: &str, default: &str) -> anyhow::Result<HeaderValue> {
+    HeaderValue::from_str(&env_or(name, default))
+        .map_err(|e| anyhow::anyhow!("Invalid {} environment variable: {}", name, e))
+}
+
+// An explicit STRICT_TRANSPORT_SECURITY value wins over the individual
+// options. Preload is refused unless it meets the preload list requirements.
+fn hsts_from_env() -> anyhow::Result<HeaderValue> {
+    if env_var("STRICT_TRANSPORT_SECURITY").is_some() {
+        return header_from_env("STRICT_TRANSPORT_SECURITY", "");
+    }
+    let max_age: u64 = env_parse("HSTS_MAX_AGE", 31_536_000)?;
+    let include_subdomains = env_flag("HSTS_INCLUDE_SUBDOMAINS", false)?;
+    let preload = env_flag("HSTS_PRELOAD", false)?;
+    if preload && (!include_subdomains || max_age < 31_536_000) {
+        anyhow::bail!("HSTS_PRELOAD requires HSTS_INCLUDE_SUBDOMAINS and HSTS_MAX_AGE >= 31536000");
+    }
+
+    let mut value = format!("max-age={}", max_age);
+    if include_subdomains {
+        value.push_str("; includeSubDomains");
+    }
+    if preload {
+        value.push_str("; preload");
+    }
+    Ok(HeaderValue::from_str(&value)?)
+}
+
+/// Whether `X-Forwarded-Proto` from a TLS-terminating proxy is believed.
+///
+/// Off by default: the header is client-controlled unless a proxy in front of
+/// the server overwrites it, so only enable this behind such a proxy.
+pub fn trust_proxy_from_env() -> anyhow::Result<bool> {
+    env_flag("TRUST_PROXY", false)
+}
+
+pub fn is_https_request(request: &Request, trust_proxy: bool) -> bool {
+    request.uri().scheme_str() == Some("https")
+        || (trust_proxy
+            && request
+                .headers()
+                .get(FORWARDED_PROTO_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|proto| proto.eq_ignore_ascii_case("https")))
+}
+
+pub async fn security_headers(
+    State(config): State<Arc<SecurityHeadersConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_https = is_https_request(&request, config.trust_proxy);
+    let mut response = next.run(request).await;
+    if !config.enabled {
+        return response;
+    }
+
+    let headers = response.headers_mut();
+    headers.insert(X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    headers.insert(X_FRAME_OPTIONS, config.frame_options.clone());
+    headers.insert(REFERRER_POLICY, config.referrer_policy.clone());
+    if is_https {
+        headers.insert(STRICT_TRANSPORT_SECURITY, config.hsts.clone());
+    }
+    response
+}
+
+pub async fn docs_content_security_policy(
+    State(config): State<Arc<SecurityHeadersConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+    if config.enabled {
+        response.headers_mut().insert(
+            CONTENT_SECURITY_POLICY,
+            config.docs_content_security_policy.clone(),
+        );
+    }
+    response
+}
@@ -0,0 +1,62 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::env_parse;
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use time::Duration;
+use tower_sessions::{Expiry, Session};
+
+pub const SESSION_INACTIVITY: Duration = Duration::days(1);
+
+#[derive(Clone, Copy, Debug)]
+pub struct SessionExpiryJitter {
+    pub percent: u8,
+}
+
+impl SessionExpiryJitter {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let percent = env_parse("SESSION_EXPIRY_JITTER_PERCENT", 0)?;
+        if percent > 100 {
+            anyhow::bail!("SESSION_EXPIRY_JITTER_PERCENT must be between 0 and 100");
+        }
+        Ok(Self { percent })
+    }
+
+    fn jittered_inactivity(&self) -> Duration {
+        let max_extra = SESSION_INACTIVITY.whole_seconds() * i64::from(self.percent) / 100;
+        SESSION_INACTIVITY + Duration::seconds(rand::random_range(0..=max_extra))
+    }
+}
+
+// Sessions are only written back when modified, so extending the expiry of
+// every modified session spreads out sessions created in the same burst.
+pub async fn session_expiry_jitter(
+    State(jitter): State<SessionExpiryJitter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let session = request.extensions().get::<Session>().cloned();
+    let response = next.run(request).await;
+    if jitter.percent > 0
+        && let Some(session) = session
+        && session.is_modified()
+    {
+        session.set_expiry(Some(Expiry::OnInactivity(jitter.jittered_inactivity())));
+    }
+    response
+}
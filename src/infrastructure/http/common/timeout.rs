@@ -0,0 +1,110 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::deadline::with_deadline;
+use crate::infrastructure::http::error_handler::{ApiError, ErrorKind};
+use axum::BoxError;
+use axum::extract::{Request, State};
+use axum::middleware::Next;
+use axum::response::Response;
+use std::time::Duration;
+use tokio::time::Instant;
+use tower::timeout::error::Elapsed;
+
+pub async fn handle_timeout_error(error: BoxError) -> ApiError {
+    if error.is::<Elapsed>() {
+        tracing::warn!("Request timed out");
+        return ApiError::new("request_timeout".to_string(), ErrorKind::GatewayTimeout);
+    }
+    tracing::error!("Unhandled middleware error: {}", error);
+    ApiError::new("internal_error".to_string(), ErrorKind::InternalServerError)
+}
+
+// Dropped before completion means the client went away (timeouts are handled
+// further in and still complete with a 504).
+struct CancellationGuard {
+    completed: bool,
+}
+
+impl Drop for CancellationGuard {
+    fn drop(&mut self) {
+        if !self.completed {
+            tracing::info!("Request cancelled by client");
+        }
+    }
+}
+
+pub async fn log_cancellation(request: Request, next: Next) -> Response {
+    let mut guard = CancellationGuard { completed: false };
+    let response = next.run(request).await;
+    guard.completed = true;
+    response
+}
+
+// Sits inside the TimeoutLayer and exposes the same deadline to the
+// repositories, which stop waiting on the database once it has passed.
+pub async fn propagate_deadline(
+    State(timeout): State<Duration>,
+    request: Request,
+    next: Next,
+) -> Response {
+    with_deadline(Instant::now() + timeout, next.run(request)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::body::{Body, to_bytes};
+    use axum::error_handling::HandleErrorLayer;
+    use axum::http::StatusCode;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::routing::get;
+    use tower::timeout::TimeoutLayer;
+    use tower::{ServiceBuilder, ServiceExt};
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_handler_times_out_with_api_error() {
+        let router = Router::new()
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    "done"
+                }),
+            )
+            .layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(handle_timeout_error))
+                    .layer(TimeoutLayer::new(Duration::from_secs(1))),
+            );
+        let request = axum::http::Request::builder()
+            .uri("/slow")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(
+            response.headers().get(CONTENT_TYPE).unwrap(),
+            "application/json; charset=utf-8"
+        );
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["message"], "request_timeout");
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use axum::extract::{Request, State};
+use axum::http::Uri;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Redirect, Response};
+use std::str::FromStr;
+
+// The documentation UIs rely on their exact paths (Swagger UI redirects
+// /swagger-ui to /swagger-ui/), so they are left untouched.
+const EXCLUDED_PREFIXES: [&str; 3] = ["/swagger-ui", "/scalar", "/api-docs"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrailingSlashMode {
+    Off,
+    Rewrite,
+    Redirect,
+}
+
+impl FromStr for TrailingSlashMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "rewrite" => Ok(Self::Rewrite),
+            "redirect" => Ok(Self::Redirect),
+            _ => Err(format!("expected off, rewrite or redirect, got {}", value)),
+        }
+    }
+}
+
+// Must wrap the router from the outside: middleware added with Router::layer
+// runs after routing and a rewritten URI would never be matched.
+pub async fn trailing_slash(
+    State(mode): State<TrailingSlashMode>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if mode == TrailingSlashMode::Off {
+        return next.run(request).await;
+    }
+    let Some(uri) = normalized_uri(request.uri()) else {
+        return next.run(request).await;
+    };
+
+    if mode == TrailingSlashMode::Redirect {
+        return Redirect::permanent(&uri.to_string()).into_response();
+    }
+    *request.uri_mut() = uri;
+    next.run(request).await
+}
+
+fn normalized_uri(uri: &Uri) -> Option<Uri> {
+    let path = uri.path();
+    if path == "/"
+        || !path.ends_with('/')
+        || EXCLUDED_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+    {
+        return None;
+    }
+
+    let trimmed = match path.trim_end_matches('/') {
+        "" => "/",
+        trimmed => trimmed,
+    };
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{}?{}", trimmed, query),
+        None => trimmed.to_string(),
+    };
+    path_and_query.parse().ok()
+}
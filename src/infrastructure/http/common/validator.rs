@@ -14,44 +14,101 @@
  * limitations under the License.
  */
 
-use crate::infrastructure::http::error_handler::{ApiError, ErrorKind};
+use crate::infrastructure::http::error_handler::{ApiError, ErrorDetail, ErrorKind};
 use axum::Json;
 use axum::body::Body;
 use axum::extract::rejection::JsonRejection;
 use axum::extract::{FromRequest, Request};
 use serde::de::DeserializeOwned;
-use std::future::Future;
-use validator::Validate;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::sync::OnceLock;
+use validator::{Validate, ValidationError};
+
+static STRICT_JSON: OnceLock<bool> = OnceLock::new();
+
+/// Opt-in: reject request bodies carrying fields the DTO does not declare.
+pub fn set_strict_json(enabled: bool) {
+    let _ = STRICT_JSON.set(enabled);
+}
+
+fn is_strict_json() -> bool {
+    STRICT_JSON.get().copied().unwrap_or(false)
+}
 
 #[derive(Debug)]
 pub struct ValidatedJson<T>(pub T);
 
-#[async_trait::async_trait]
+/// Parses the body like `ValidatedJson`, strict mode included, but leaves
+/// validation to the handler, for endpoints that report failed checks
+/// instead of rejecting the request.
+#[derive(Debug)]
+pub struct UnvalidatedJson<T>(pub T);
+
+async fn parse_json_body<T, S>(req: Request<Body>, state: &S) -> Result<T, ApiError>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+    Json<Value>: FromRequest<S, Rejection = JsonRejection>,
+{
+    let Json(body) = Json::<Value>::from_request(req, state)
+        .await
+        .map_err(|rejection| {
+            tracing::debug!("JSON parsing error: {:?}", rejection);
+            ApiError::new("invalid_json_format".to_string(), ErrorKind::BadRequest)
+        })?;
+
+    let mut unknown_fields = Vec::new();
+    let value: T = if is_strict_json() {
+        serde_ignored::deserialize(body, |path| unknown_fields.push(path.to_string()))
+    } else {
+        T::deserialize(body)
+    }
+    .map_err(|e| {
+        tracing::debug!("JSON parsing error: {:?}", e);
+        ApiError::new("invalid_json_format".to_string(), ErrorKind::BadRequest)
+    })?;
+
+    if !unknown_fields.is_empty() {
+        let details: Vec<ErrorDetail> = unknown_fields
+            .into_iter()
+            .map(|field| ErrorDetail::from([(field, "unknown_field".to_string())]))
+            .collect();
+        return Err(ApiError::with_details(
+            "unknown_fields_error".to_string(),
+            details,
+            ErrorKind::BadRequest,
+        ));
+    }
+
+    Ok(value)
+}
+
 impl<T, S> FromRequest<S> for ValidatedJson<T>
 where
     T: DeserializeOwned + Validate,
     S: Send + Sync,
-    Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    Json<Value>: FromRequest<S, Rejection = JsonRejection>,
 {
     type Rejection = ApiError;
 
-    fn from_request(
-        req: Request<Body>,
-        state: &S,
-    ) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
-        async move {
-            let Json(value) = Json::<T>::from_request(req, state)
-                .await
-                .map_err(|rejection| {
-                    tracing::debug!("JSON parsing error: {:?}", rejection);
-                    ApiError::new("invalid_json_format".to_string(), ErrorKind::BadRequest)
-                })?;
-
-            value
-                .validate()
-                .map_err(|validation_errors| ApiError::from(validation_errors))?;
-            Ok(ValidatedJson(value))
-        }
+    async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+        let value: T = parse_json_body(req, state).await?;
+        value.validate().map_err(ApiError::from)?;
+        Ok(ValidatedJson(value))
+    }
+}
+
+impl<T, S> FromRequest<S> for UnvalidatedJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+    Json<Value>: FromRequest<S, Rejection = JsonRejection>,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+        parse_json_body(req, state).await.map(UnvalidatedJson)
     }
 }
 
@@ -67,3 +124,41 @@ where
         self.validate().map_err(ApiError::from)
     }
 }
+
+/// Trims and lowercases an email while deserializing, so `  John@Example.com `
+/// matches the stored `john@example.com` on login as well as on registration.
+pub fn normalize_email<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let email = String::deserialize(deserializer)?;
+    Ok(email.trim().to_lowercase())
+}
+
+/// Accepts a BCP-47 language tag such as `en`, `pt-BR` or `zh-Hant-TW`.
+pub fn validate_locale(locale: &str) -> Result<(), ValidationError> {
+    let mut subtags = locale.split('-');
+    let language_ok = subtags.next().is_some_and(|language| {
+        matches!(language.len(), 2..=3 | 5..=8)
+            && language.chars().all(|c| c.is_ascii_alphabetic())
+    });
+    let subtags_ok = subtags.all(|subtag| {
+        (1..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+
+    if locale.len() <= 35 && language_ok && subtags_ok {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_locale").with_message("invalid_locale".into()))
+    }
+}
+
+/// Accepts an IANA time zone name such as `Europe/Paris`.
+pub fn validate_timezone(timezone: &str) -> Result<(), ValidationError> {
+    timezone
+        .parse::<chrono_tz::Tz>()
+        .map(|_| ())
+        .map_err(|_| {
+            ValidationError::new("invalid_timezone").with_message("invalid_timezone".into())
+        })
+}
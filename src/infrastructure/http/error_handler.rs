@@ -15,24 +15,61 @@
  */
 
 use crate::domain::common::DomainError;
-use axum::http::StatusCode;
+use crate::infrastructure::http::common::request_id::current_request_id;
+use axum::http::header::{CONTENT_TYPE, RETRY_AFTER};
+use axum::http::{HeaderValue, StatusCode};
 use axum::response::IntoResponse;
 use axum::Json;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::sync::OnceLock;
 use utoipa::ToSchema;
 use validator::ValidationErrors;
 
 pub type ApiResult<T> = Result<Json<T>, ApiError>;
 pub type ErrorDetail = HashMap<String, String>;
 
+pub const JSON_CONTENT_TYPE: &str = "application/json; charset=utf-8";
+
+static RETRY_AFTER_SECS: OnceLock<u64> = OnceLock::new();
+static DEBUG_ERRORS: OnceLock<bool> = OnceLock::new();
+
+/// Seconds advertised in `Retry-After` on 503 responses.
+pub fn set_retry_after(seconds: u64) {
+    let _ = RETRY_AFTER_SECS.set(seconds);
+}
+
+/// Non-production only: expose the underlying cause in the `details` of 500
+/// responses.
+pub fn set_debug_errors(enabled: bool) {
+    let _ = DEBUG_ERRORS.set(enabled);
+}
+
+fn internal_error(cause: String) -> ApiError {
+    if DEBUG_ERRORS.get().copied().unwrap_or(false) {
+        return ApiError::with_details(
+            "internal_error".to_string(),
+            vec![HashMap::from([("cause".to_string(), cause)])],
+            ErrorKind::InternalServerError,
+        );
+    }
+    ApiError::new(
+        "internal_error".to_string(),
+        ErrorKind::InternalServerError,
+    )
+}
+
 #[derive(Debug)]
 pub enum ErrorKind {
     BadRequest,
     Unauthorized,
+    Forbidden,
     NotFound,
     Conflict,
+    TooManyRequests,
     InternalServerError,
+    ServiceUnavailable,
+    GatewayTimeout,
 }
 
 impl ErrorKind {
@@ -40,9 +77,13 @@ impl ErrorKind {
         match self {
             Self::BadRequest => StatusCode::BAD_REQUEST,
             Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::Forbidden => StatusCode::FORBIDDEN,
             Self::NotFound => StatusCode::NOT_FOUND,
             Self::Conflict => StatusCode::CONFLICT,
+            Self::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
             Self::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            Self::GatewayTimeout => StatusCode::GATEWAY_TIMEOUT,
         }
     }
 }
@@ -54,6 +95,14 @@ pub struct ApiError {
     pub details: Vec<ErrorDetail>,
     #[serde(skip)]
     pub kind: ErrorKind,
+    /// Seconds advertised in `Retry-After`; 503s default to the configured
+    /// value.
+    #[serde(skip)]
+    pub retry_after: Option<u64>,
+    /// Id of the failed request, for matching a report to the logs. Filled
+    /// in from the request scope when the response is built.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl ApiError {
@@ -62,47 +111,85 @@ impl ApiError {
             message,
             details: vec![],
             kind,
+            retry_after: None,
+            request_id: None,
         }
     }
 
-    fn with_details(message: String, details: Vec<ErrorDetail>, kind: ErrorKind) -> Self {
+    pub fn with_details(message: String, details: Vec<ErrorDetail>, kind: ErrorKind) -> Self {
         Self {
             message,
             details,
             kind,
+            retry_after: None,
+            request_id: None,
         }
     }
+
+    pub fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = Some(seconds);
+        self
+    }
 }
 
 impl IntoResponse for ApiError {
-    fn into_response(self) -> axum::response::Response {
-        (self.kind.status_code(), Json(self)).into_response()
+    fn into_response(mut self) -> axum::response::Response {
+        if self.request_id.is_none() {
+            self.request_id = current_request_id();
+        }
+        let retry_after = self.retry_after.or_else(|| {
+            matches!(self.kind, ErrorKind::ServiceUnavailable)
+                .then(|| RETRY_AFTER_SECS.get().copied().unwrap_or(5))
+        });
+        let mut response = (
+            self.kind.status_code(),
+            [(CONTENT_TYPE, HeaderValue::from_static(JSON_CONTENT_TYPE))],
+            Json(self),
+        )
+            .into_response();
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
     }
 }
 
 impl From<DomainError> for ApiError {
     fn from(error: DomainError) -> Self {
         match error {
-            DomainError::InternalError => {
-                tracing::error!("Internal server error occurred");
-                ApiError::new(
-                    "internal_error".to_string(),
-                    ErrorKind::InternalServerError,
-                )
+            DomainError::InternalError(cause) => {
+                tracing::error!("Internal server error occurred: {}", cause);
+                internal_error(cause)
             }
             DomainError::ConflictError(message) => {
                 tracing::warn!("Conflict error: {}", message);
                 ApiError::new(message, ErrorKind::Conflict)
             }
+            DomainError::ServiceUnavailable => ApiError::new(
+                error.to_string(),
+                ErrorKind::ServiceUnavailable,
+            ),
             DomainError::NotFoundError => {
                 ApiError::new("not_found_error".to_string(), ErrorKind::NotFound)
             }
             DomainError::PasswordNotMatchError
             | DomainError::AuthenticationFailed
-            | DomainError::InvalidCredentials => {
+            | DomainError::InvalidCredentials
+            | DomainError::InvalidRefreshToken
+            | DomainError::InvalidMagicLink => {
                 tracing::warn!("Authentication error: {}", error);
                 ApiError::new(error.to_string(), ErrorKind::Unauthorized)
             }
+            DomainError::ValidationError { code, message } => {
+                tracing::debug!("Validation error: {} ({})", code, message);
+                ApiError::with_details(
+                    code,
+                    vec![HashMap::from([("message".to_string(), message)])],
+                    ErrorKind::BadRequest,
+                )
+            }
             DomainError::SamePasswordError => {
                 tracing::warn!("Same password validation error: {}", error);
                 ApiError::new(error.to_string(), ErrorKind::BadRequest)
@@ -146,9 +233,6 @@ impl From<ValidationErrors> for ApiError {
 impl From<anyhow::Error> for ApiError {
     fn from(error: anyhow::Error) -> Self {
         tracing::error!("Unexpected error: {:?}", error);
-        ApiError::new(
-            "internal_error".to_string(),
-            ErrorKind::InternalServerError,
-        )
+        internal_error(format!("{:#}", error))
     }
 }
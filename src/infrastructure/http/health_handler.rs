@@ -13,8 +13,10 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use crate::domain::health::Health;
 use crate::infrastructure::app_state::AppState;
 use axum::extract::State;
+use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::Json;
 use serde::Serialize;
@@ -26,6 +28,20 @@ const HEALTH_TAG: &str = "Health";
 #[derive(Serialize, Debug, ToSchema)]
 pub struct HealthResponse {
     healthy: bool,
+    /// Healthy, but the session store round trip exceeded the latency threshold.
+    degraded: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_store_latency_ms: Option<u64>,
+}
+
+impl From<Health> for HealthResponse {
+    fn from(health: Health) -> Self {
+        Self {
+            healthy: health.healthy,
+            degraded: health.degraded,
+            session_store_latency_ms: health.session_store_latency_ms,
+        }
+    }
 }
 
 #[utoipa::path(
@@ -39,7 +55,25 @@ pub struct HealthResponse {
 )]
 pub async fn health_check(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
     let result = app_state.health_service.health_check().await;
+    Json(HealthResponse::from(result))
+}
+
+// Probes served on the management port only, outside the public API and its
+// OpenAPI document.
+pub async fn liveness() -> impl IntoResponse {
     Json(HealthResponse {
-        healthy: result.healthy,
+        healthy: true,
+        degraded: false,
+        session_store_latency_ms: None,
     })
 }
+
+pub async fn readiness(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
+    let result = app_state.health_service.health_check().await;
+    let status = if result.healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(HealthResponse::from(result)))
+}
@@ -0,0 +1,58 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::{env_parse, env_var};
+use std::fs;
+use std::time::Duration;
+
+/// Builds the single outbound HTTP client shared by every integration.
+/// Proxies come from the standard `HTTP_PROXY`, `HTTPS_PROXY` and `NO_PROXY`
+/// variables; `OUTBOUND_CA_BUNDLE` adds PEM certificates to the system roots.
+pub fn build_http_client() -> anyhow::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .connect_timeout(Duration::from_millis(env_parse(
+            "HTTP_CLIENT_CONNECT_TIMEOUT_MS",
+            5000,
+        )?))
+        .timeout(Duration::from_millis(env_parse(
+            "HTTP_CLIENT_TIMEOUT_MS",
+            10000,
+        )?));
+
+    if let Some(path) = env_var("OUTBOUND_CA_BUNDLE") {
+        let pem = fs::read(&path).map_err(|e| {
+            anyhow::anyhow!("Failed to read OUTBOUND_CA_BUNDLE from {}: {}", path, e)
+        })?;
+        let certificates = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| anyhow::anyhow!("Invalid OUTBOUND_CA_BUNDLE {}: {}", path, e))?;
+        if certificates.is_empty() {
+            return Err(anyhow::anyhow!(
+                "OUTBOUND_CA_BUNDLE contains no certificates: {}",
+                path
+            ));
+        }
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    Ok(builder.build()?)
+}
@@ -0,0 +1,113 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::env_parse;
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
+use std::time::Duration;
+use tower_sessions_redis_store::fred::prelude::*;
+use tower_sessions_redis_store::fred::types::ExpireOptions;
+
+const KEY_PREFIX: &str = "login_failures";
+
+/// Counts failed logins per email and per client IP in fixed windows that
+/// start at the first failure. Redis errors fail open: an unreachable Redis
+/// must not lock everyone out.
+pub struct LoginRateLimiter {
+    pool: Pool,
+    max_failures: u64,
+    window: Duration,
+}
+
+impl LoginRateLimiter {
+    /// `None` when `LOGIN_MAX_FAILURES` is 0, which disables the limit.
+    pub fn from_env(pool: Pool) -> anyhow::Result<Option<Self>> {
+        let max_failures = env_parse("LOGIN_MAX_FAILURES", 5)?;
+        let window = Duration::from_secs(env_parse("LOGIN_FAILURE_WINDOW_SECS", 900)?);
+        if window.is_zero() {
+            anyhow::bail!("LOGIN_FAILURE_WINDOW_SECS must be greater than 0");
+        }
+        Ok((max_failures > 0).then_some(Self {
+            pool,
+            max_failures,
+            window,
+        }))
+    }
+
+    // Emails are hashed so Redis never holds them in clear.
+    fn email_key(email: &str) -> String {
+        format!(
+            "{}:email:{:x}",
+            KEY_PREFIX,
+            Sha256::digest(email.to_lowercase().as_bytes())
+        )
+    }
+
+    fn ip_key(ip: IpAddr) -> String {
+        format!("{}:ip:{}", KEY_PREFIX, ip)
+    }
+
+    /// Returns how long the caller must wait when either counter has reached
+    /// the limit.
+    pub async fn check(&self, email: &str, ip: IpAddr) -> Option<Duration> {
+        let keys = vec![Self::email_key(email), Self::ip_key(ip)];
+        let counts: Vec<Option<u64>> = match self.pool.mget(keys.clone()).await {
+            Ok(counts) => counts,
+            Err(e) => {
+                tracing::warn!("Login rate limit check unavailable: {}", e);
+                return None;
+            }
+        };
+
+        let mut retry_after = None;
+        for (key, count) in keys.iter().zip(counts) {
+            if count.unwrap_or(0) < self.max_failures {
+                continue;
+            }
+            let ttl: i64 = self.pool.ttl(key).await.unwrap_or(-1);
+            // A key without expiry should not exist; fall back to the window.
+            let wait = u64::try_from(ttl).map_or(self.window, Duration::from_secs);
+            retry_after = retry_after.max(Some(wait));
+        }
+        retry_after
+    }
+
+    pub async fn record_failure(&self, email: &str, ip: IpAddr) {
+        for key in [Self::email_key(email), Self::ip_key(ip)] {
+            let recorded = async {
+                let _: u64 = self.pool.incr(&key).await?;
+                let _: bool = self
+                    .pool
+                    .expire(&key, self.window.as_secs() as i64, Some(ExpireOptions::NX))
+                    .await?;
+                Ok::<_, Error>(())
+            };
+            if let Err(e) = recorded.await {
+                tracing::warn!("Failed to record login failure: {}", e);
+            }
+        }
+    }
+
+    /// Clears the email counter after a successful login. The IP counter is
+    /// kept: one valid account must not let an IP reset its own limit while
+    /// guessing others.
+    pub async fn reset(&self, email: &str) {
+        let deleted: Result<u64, Error> = self.pool.del(Self::email_key(email)).await;
+        if let Err(e) = deleted {
+            tracing::warn!("Failed to reset login failures: {}", e);
+        }
+    }
+}
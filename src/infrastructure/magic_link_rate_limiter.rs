@@ -0,0 +1,84 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::env_parse;
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tower_sessions_redis_store::fred::prelude::*;
+use tower_sessions_redis_store::fred::types::ExpireOptions;
+
+const KEY_PREFIX: &str = "magic_link_requests";
+
+/// Counts login link requests per email in fixed windows that start at the
+/// first request, so the endpoint cannot be used to flood an inbox. Redis
+/// errors fail open.
+pub struct MagicLinkRateLimiter {
+    pool: Pool,
+    max_requests: u64,
+    window: Duration,
+}
+
+impl MagicLinkRateLimiter {
+    /// `None` when `MAGIC_LINK_MAX_REQUESTS` is 0, which disables the limit.
+    pub fn from_env(pool: Pool) -> anyhow::Result<Option<Self>> {
+        let max_requests = env_parse("MAGIC_LINK_MAX_REQUESTS", 3)?;
+        let window = Duration::from_secs(env_parse("MAGIC_LINK_WINDOW_SECS", 900)?);
+        if window.is_zero() {
+            anyhow::bail!("MAGIC_LINK_WINDOW_SECS must be greater than 0");
+        }
+        Ok((max_requests > 0).then_some(Self {
+            pool,
+            max_requests,
+            window,
+        }))
+    }
+
+    // Emails are hashed so Redis never holds them in clear.
+    fn key(email: &str) -> String {
+        format!(
+            "{}:{:x}",
+            KEY_PREFIX,
+            Sha256::digest(email.to_lowercase().as_bytes())
+        )
+    }
+
+    /// Counts a request and returns how long the caller must wait when it
+    /// is over the limit.
+    pub async fn acquire(&self, email: &str) -> Option<Duration> {
+        let key = Self::key(email);
+        let counted = async {
+            let count: u64 = self.pool.incr(&key).await?;
+            let _: bool = self
+                .pool
+                .expire(&key, self.window.as_secs() as i64, Some(ExpireOptions::NX))
+                .await?;
+            Ok::<_, Error>(count)
+        };
+        let count = match counted.await {
+            Ok(count) => count,
+            Err(e) => {
+                tracing::warn!("Magic link rate limit unavailable: {}", e);
+                return None;
+            }
+        };
+        if count <= self.max_requests {
+            return None;
+        }
+        let ttl: i64 = self.pool.ttl(&key).await.unwrap_or(-1);
+        // A key without expiry should not exist; fall back to the window.
+        Some(u64::try_from(ttl).map_or(self.window, Duration::from_secs))
+    }
+}
@@ -15,7 +15,19 @@
  */
 pub mod app_state;
 pub mod application_health;
+pub mod auth;
+pub mod config;
+pub mod deadline;
+pub mod event_bus;
 pub mod http;
+pub mod http_client;
+pub mod login_rate_limiter;
+pub mod magic_link_rate_limiter;
+pub mod mx_email_domain;
 pub mod openapi;
 pub mod persistence;
+pub mod pwned_passwords;
+pub mod redis_pool;
+pub mod resilient_session_store;
 pub mod server;
+pub mod session_encryption;
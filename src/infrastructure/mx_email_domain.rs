@@ -0,0 +1,83 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::auth::spi::email_domain_checker::EmailDomainChecker;
+use crate::infrastructure::config::env_parse;
+use hickory_resolver::TokioResolver;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const MAX_CACHED_DOMAINS: usize = 10_000;
+
+/// Looks up MX records through the system resolver, remembering answers for
+/// `cache_ttl` so a burst of signups from one domain costs a single query.
+pub struct MxEmailDomainChecker {
+    resolver: TokioResolver,
+    timeout: Duration,
+    cache_ttl: Duration,
+    cache: Mutex<HashMap<String, (bool, Instant)>>,
+}
+
+impl MxEmailDomainChecker {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let resolver = TokioResolver::builder_tokio()
+            .map_err(|e| anyhow::anyhow!("Failed to read the system DNS configuration: {}", e))?
+            .build();
+        Ok(Self {
+            resolver,
+            timeout: Duration::from_millis(env_parse("EMAIL_MX_TIMEOUT_MS", 1000)?),
+            cache_ttl: Duration::from_secs(env_parse("EMAIL_MX_CACHE_SECS", 300)?),
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn cached(&self, domain: &str) -> Option<bool> {
+        let cache = self.cache.lock().unwrap();
+        cache
+            .get(domain)
+            .filter(|(_, checked_at)| checked_at.elapsed() < self.cache_ttl)
+            .map(|(accepts_mail, _)| *accepts_mail)
+    }
+
+    fn remember(&self, domain: &str, accepts_mail: bool) {
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= MAX_CACHED_DOMAINS {
+            cache.clear();
+        }
+        cache.insert(domain.to_string(), (accepts_mail, Instant::now()));
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailDomainChecker for MxEmailDomainChecker {
+    async fn accepts_mail(&self, domain: &str) -> anyhow::Result<bool> {
+        if let Some(accepts_mail) = self.cached(domain) {
+            return Ok(accepts_mail);
+        }
+
+        let lookup = tokio::time::timeout(self.timeout, self.resolver.mx_lookup(domain)).await?;
+        let accepts_mail = match lookup {
+            // A single "." exchange is a null MX (RFC 7505): the domain
+            // explicitly accepts no mail.
+            Ok(records) => records.iter().any(|mx| !mx.exchange().is_root()),
+            Err(e) if e.is_no_records_found() => false,
+            Err(e) => return Err(e.into()),
+        };
+        self.remember(domain, accepts_mail);
+        Ok(accepts_mail)
+    }
+}
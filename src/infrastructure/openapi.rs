@@ -14,7 +14,9 @@
  * limitations under the License.
  */
 
-use utoipa::openapi::security::{ApiKey, ApiKeyValue, SecurityScheme};
+use utoipa::openapi::security::{
+    ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme,
+};
 use utoipa::{Modify, OpenApi};
 use utoipa_axum::router::OpenApiRouter;
 
@@ -41,5 +43,13 @@ impl Modify for SecurityAddon {
         let description = "The session cookie is used by the web UI to authenticate users.";
         let cookie = ApiKey::Cookie(ApiKeyValue::with_description("id", description));
         components.add_security_scheme("cookie", SecurityScheme::ApiKey(cookie));
+        let bearer = HttpBuilder::new()
+            .scheme(HttpAuthScheme::Bearer)
+            .bearer_format("JWT")
+            .description(Some(
+                "Access token returned by login or register with auth_mode token.",
+            ))
+            .build();
+        components.add_security_scheme("bearer", SecurityScheme::Http(bearer));
     }
 }
@@ -13,16 +13,50 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-use sea_orm::ConnectOptions;
+use crate::infrastructure::config::{env_or_file, env_var};
+use sea_orm::{ConnectOptions, DatabaseConnection};
 
-pub async fn establish_connection() -> anyhow::Result<sea_orm::DatabaseConnection> {
-    let database_url = std::env::var("DATABASE_URL")
-        .map_err(|_| anyhow::anyhow!("DATABASE_URL environment variable must be set"))?;
-    let mut opt = ConnectOptions::new(&database_url);
+/// Writes go to `primary`; reads that tolerate replication lag may use
+/// `replica`, which is the primary itself when no replica is configured.
+#[derive(Clone)]
+pub struct DatabaseConnections {
+    pub primary: DatabaseConnection,
+    pub replica: DatabaseConnection,
+}
+
+pub async fn establish_connection() -> anyhow::Result<DatabaseConnections> {
+    let database_url = env_or_file("DATABASE_URL")?.ok_or_else(|| {
+        anyhow::anyhow!("DATABASE_URL or DATABASE_URL_FILE environment variable must be set")
+    })?;
+    let primary = connect(&database_url)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to connect to the database: {}", e))?;
+    let replica = match env_or_file("DATABASE_REPLICA_URL")? {
+        Some(replica_url) => connect(&replica_url)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to connect to the database replica: {}", e))?,
+        None => primary.clone(),
+    };
+    Ok(DatabaseConnections { primary, replica })
+}
+
+async fn connect(database_url: &str) -> anyhow::Result<DatabaseConnection> {
+    let mut opt = ConnectOptions::new(database_url);
     opt.max_connections(20);
     opt.min_connections(5);
     opt.sqlx_logging(true);
-    sea_orm::Database::connect(opt)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to connect to the database: {}", e))
+    // Dropping a query future only stops the client waiting; a statement
+    // timeout also stops the server working on it.
+    if let Some(statement_timeout_ms) = env_var("DB_STATEMENT_TIMEOUT_MS") {
+        let statement_timeout_ms: u64 = statement_timeout_ms.parse().map_err(|e| {
+            anyhow::anyhow!(
+                "Invalid DB_STATEMENT_TIMEOUT_MS environment variable: {}",
+                e
+            )
+        })?;
+        opt.map_sqlx_postgres_opts(move |options| {
+            options.options([("statement_timeout", statement_timeout_ms.to_string())])
+        });
+    }
+    Ok(sea_orm::Database::connect(opt).await?)
 }
@@ -18,4 +18,11 @@
 
 pub mod prelude;
 
+pub mod email_verification_tokens;
+pub mod magic_link_tokens;
+pub mod oauth_identities;
+pub mod password_reset_tokens;
+pub mod pending_email_changes;
+pub mod refresh_tokens;
+pub mod user_sessions;
 pub mod users;
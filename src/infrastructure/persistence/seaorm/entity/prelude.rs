@@ -16,4 +16,11 @@
 
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+pub use super::email_verification_tokens::Entity as EmailVerificationTokens;
+pub use super::magic_link_tokens::Entity as MagicLinkTokens;
+pub use super::oauth_identities::Entity as OAuthIdentities;
+pub use super::password_reset_tokens::Entity as PasswordResetTokens;
+pub use super::pending_email_changes::Entity as PendingEmailChanges;
+pub use super::refresh_tokens::Entity as RefreshTokens;
+pub use super::user_sessions::Entity as UserSessions;
 pub use super::users::Entity as Users;
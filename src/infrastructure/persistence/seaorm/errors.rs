@@ -0,0 +1,43 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::domain::common::{StoreUnavailable, UniqueViolation};
+use crate::infrastructure::persistence::seaorm::retry::is_connection_error;
+use sea_orm::{DbErr, SqlErr};
+
+/// Maps a unique-constraint violation to the domain field it protects, using
+/// the `(constraint name, field)` pairs of the calling repository.
+pub fn unique_violation(
+    error: &DbErr,
+    constraints: &[(&str, &'static str)],
+) -> Option<UniqueViolation> {
+    let Some(SqlErr::UniqueConstraintViolation(message)) = error.sql_err() else {
+        return None;
+    };
+    constraints
+        .iter()
+        .find(|(constraint, _)| message.contains(constraint))
+        .map(|(_, field)| UniqueViolation { field })
+}
+
+/// Converts a database error for the application layer, marking connection
+/// failures that survived the retries as `StoreUnavailable`.
+pub fn map_db_error(error: DbErr) -> anyhow::Error {
+    if is_connection_error(&error) {
+        return anyhow::Error::new(StoreUnavailable).context(error.to_string());
+    }
+    error.into()
+}
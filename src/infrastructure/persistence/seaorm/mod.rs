@@ -15,4 +15,6 @@
  */
 pub mod db;
 pub mod entity;
+pub mod errors;
 pub mod repository;
+pub mod retry;
@@ -0,0 +1,121 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::auth::spi::email_verification_repository::EmailVerificationRepository;
+use crate::domain::common::DateTimeUtc;
+use crate::domain::email_verification::EmailVerificationToken;
+use crate::infrastructure::persistence::seaorm::entity::email_verification_tokens;
+use crate::infrastructure::persistence::seaorm::errors::map_db_error;
+use crate::infrastructure::persistence::seaorm::retry::DbRetryPolicy;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+pub struct SeaOrmEmailVerificationRepository {
+    pub db: DatabaseConnection,
+    pub retry: DbRetryPolicy,
+}
+
+impl SeaOrmEmailVerificationRepository {
+    fn model_to_token(model: email_verification_tokens::Model) -> EmailVerificationToken {
+        EmailVerificationToken {
+            id: model.id,
+            user_id: model.user_id,
+            token_hash: model.token_hash,
+            expires_at: model.expires_at,
+            used_at: model.used_at,
+            created_at: model.created_at,
+        }
+    }
+
+    fn token_to_active_model(
+        token: EmailVerificationToken,
+    ) -> email_verification_tokens::ActiveModel {
+        email_verification_tokens::ActiveModel {
+            id: Set(token.id),
+            user_id: Set(token.user_id),
+            token_hash: Set(token.token_hash),
+            expires_at: Set(token.expires_at),
+            used_at: Set(token.used_at),
+            created_at: Set(token.created_at),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EmailVerificationRepository for SeaOrmEmailVerificationRepository {
+    async fn save(&self, token: EmailVerificationToken) -> anyhow::Result<EmailVerificationToken> {
+        let saved_token = self
+            .retry
+            .run_write("save email verification token", || {
+                email_verification_tokens::Entity::insert(Self::token_to_active_model(
+                    token.clone(),
+                ))
+                .exec_with_returning(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(Self::model_to_token(saved_token))
+    }
+
+    async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<EmailVerificationToken>> {
+        let found_token = self
+            .retry
+            .run_read("find email verification token", || {
+                email_verification_tokens::Entity::find()
+                    .filter(email_verification_tokens::Column::TokenHash.eq(token_hash))
+                    .one(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
+            .map(Self::model_to_token);
+        Ok(found_token)
+    }
+
+    async fn mark_used(&self, id: &str, used_at: DateTimeUtc) -> anyhow::Result<bool> {
+        let result = self
+            .retry
+            .run_write("mark email verification token used", || {
+                email_verification_tokens::Entity::update_many()
+                    .col_expr(
+                        email_verification_tokens::Column::UsedAt,
+                        Expr::value(used_at),
+                    )
+                    .filter(email_verification_tokens::Column::Id.eq(id))
+                    .filter(email_verification_tokens::Column::UsedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(result.rows_affected == 1)
+    }
+
+    async fn invalidate_for_user(&self, user_id: &str, at: DateTimeUtc) -> anyhow::Result<()> {
+        self.retry
+            .run_write("invalidate email verification tokens", || {
+                email_verification_tokens::Entity::update_many()
+                    .col_expr(email_verification_tokens::Column::UsedAt, Expr::value(at))
+                    .filter(email_verification_tokens::Column::UserId.eq(user_id))
+                    .filter(email_verification_tokens::Column::UsedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
+}
@@ -13,4 +13,11 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+pub mod email_verification_repository;
+pub mod magic_link_repository;
+pub mod oauth_identity_repository;
+pub mod password_reset_repository;
+pub mod pending_email_change_repository;
+pub mod refresh_token_repository;
+pub mod session_repository;
 pub mod user_repository;
@@ -0,0 +1,87 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::auth::spi::oauth_identity_repository::OAuthIdentityRepository;
+use crate::domain::oauth::OAuthIdentity;
+use crate::infrastructure::persistence::seaorm::entity::oauth_identities;
+use crate::infrastructure::persistence::seaorm::errors::{map_db_error, unique_violation};
+use crate::infrastructure::persistence::seaorm::retry::DbRetryPolicy;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+const UNIQUE_CONSTRAINTS: [(&str, &str); 1] =
+    [("oauth_identities_provider_subject_key", "oauth_identity")];
+
+pub struct SeaOrmOAuthIdentityRepository {
+    pub db: DatabaseConnection,
+    pub retry: DbRetryPolicy,
+}
+
+impl SeaOrmOAuthIdentityRepository {
+    fn model_to_identity(model: oauth_identities::Model) -> OAuthIdentity {
+        OAuthIdentity {
+            id: model.id,
+            user_id: model.user_id,
+            provider: model.provider,
+            subject: model.subject,
+            created_at: model.created_at,
+        }
+    }
+
+    fn identity_to_active_model(identity: OAuthIdentity) -> oauth_identities::ActiveModel {
+        oauth_identities::ActiveModel {
+            id: Set(identity.id),
+            user_id: Set(identity.user_id),
+            provider: Set(identity.provider),
+            subject: Set(identity.subject),
+            created_at: Set(identity.created_at),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl OAuthIdentityRepository for SeaOrmOAuthIdentityRepository {
+    async fn find(&self, provider: &str, subject: &str) -> anyhow::Result<Option<OAuthIdentity>> {
+        let found_identity = self
+            .retry
+            .run_read("find oauth identity", || {
+                oauth_identities::Entity::find()
+                    .filter(oauth_identities::Column::Provider.eq(provider))
+                    .filter(oauth_identities::Column::Subject.eq(subject))
+                    .one(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
+            .map(Self::model_to_identity);
+        Ok(found_identity)
+    }
+
+    async fn save(&self, identity: OAuthIdentity) -> anyhow::Result<OAuthIdentity> {
+        let saved_identity = self
+            .retry
+            .run_write("save oauth identity", || {
+                oauth_identities::Entity::insert(Self::identity_to_active_model(identity.clone()))
+                    .exec_with_returning(&self.db)
+            })
+            .await
+            .map_err(
+                |error| match unique_violation(&error, &UNIQUE_CONSTRAINTS) {
+                    Some(violation) => violation.into(),
+                    None => map_db_error(error),
+                },
+            )?;
+        Ok(Self::model_to_identity(saved_identity))
+    }
+}
@@ -0,0 +1,114 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::auth::spi::password_reset_repository::PasswordResetRepository;
+use crate::domain::common::DateTimeUtc;
+use crate::domain::password_reset::PasswordResetToken;
+use crate::infrastructure::persistence::seaorm::entity::password_reset_tokens;
+use crate::infrastructure::persistence::seaorm::errors::map_db_error;
+use crate::infrastructure::persistence::seaorm::retry::DbRetryPolicy;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+pub struct SeaOrmPasswordResetRepository {
+    pub db: DatabaseConnection,
+    pub retry: DbRetryPolicy,
+}
+
+impl SeaOrmPasswordResetRepository {
+    fn model_to_token(model: password_reset_tokens::Model) -> PasswordResetToken {
+        PasswordResetToken {
+            id: model.id,
+            user_id: model.user_id,
+            token_hash: model.token_hash,
+            expires_at: model.expires_at,
+            used_at: model.used_at,
+            created_at: model.created_at,
+        }
+    }
+
+    fn token_to_active_model(token: PasswordResetToken) -> password_reset_tokens::ActiveModel {
+        password_reset_tokens::ActiveModel {
+            id: Set(token.id),
+            user_id: Set(token.user_id),
+            token_hash: Set(token.token_hash),
+            expires_at: Set(token.expires_at),
+            used_at: Set(token.used_at),
+            created_at: Set(token.created_at),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PasswordResetRepository for SeaOrmPasswordResetRepository {
+    async fn save(&self, token: PasswordResetToken) -> anyhow::Result<PasswordResetToken> {
+        let saved_token = self
+            .retry
+            .run_write("save password reset token", || {
+                password_reset_tokens::Entity::insert(Self::token_to_active_model(token.clone()))
+                    .exec_with_returning(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(Self::model_to_token(saved_token))
+    }
+
+    async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<PasswordResetToken>> {
+        let found_token = self
+            .retry
+            .run_read("find password reset token", || {
+                password_reset_tokens::Entity::find()
+                    .filter(password_reset_tokens::Column::TokenHash.eq(token_hash))
+                    .one(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
+            .map(Self::model_to_token);
+        Ok(found_token)
+    }
+
+    async fn mark_used(&self, id: &str, used_at: DateTimeUtc) -> anyhow::Result<bool> {
+        let result = self
+            .retry
+            .run_write("mark password reset token used", || {
+                password_reset_tokens::Entity::update_many()
+                    .col_expr(password_reset_tokens::Column::UsedAt, Expr::value(used_at))
+                    .filter(password_reset_tokens::Column::Id.eq(id))
+                    .filter(password_reset_tokens::Column::UsedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(result.rows_affected == 1)
+    }
+
+    async fn invalidate_for_user(&self, user_id: &str, at: DateTimeUtc) -> anyhow::Result<()> {
+        self.retry
+            .run_write("invalidate password reset tokens", || {
+                password_reset_tokens::Entity::update_many()
+                    .col_expr(password_reset_tokens::Column::UsedAt, Expr::value(at))
+                    .filter(password_reset_tokens::Column::UserId.eq(user_id))
+                    .filter(password_reset_tokens::Column::UsedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::auth::spi::pending_email_change_repository::PendingEmailChangeRepository;
+use crate::domain::common::DateTimeUtc;
+use crate::domain::email_change::PendingEmailChange;
+use crate::infrastructure::persistence::seaorm::entity::pending_email_changes;
+use crate::infrastructure::persistence::seaorm::errors::map_db_error;
+use crate::infrastructure::persistence::seaorm::retry::DbRetryPolicy;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+pub struct SeaOrmPendingEmailChangeRepository {
+    pub db: DatabaseConnection,
+    pub retry: DbRetryPolicy,
+}
+
+impl SeaOrmPendingEmailChangeRepository {
+    fn model_to_change(model: pending_email_changes::Model) -> PendingEmailChange {
+        PendingEmailChange {
+            id: model.id,
+            user_id: model.user_id,
+            new_email: model.new_email,
+            token_hash: model.token_hash,
+            expires_at: model.expires_at,
+            used_at: model.used_at,
+            created_at: model.created_at,
+        }
+    }
+
+    fn change_to_active_model(change: PendingEmailChange) -> pending_email_changes::ActiveModel {
+        pending_email_changes::ActiveModel {
+            id: Set(change.id),
+            user_id: Set(change.user_id),
+            new_email: Set(change.new_email),
+            token_hash: Set(change.token_hash),
+            expires_at: Set(change.expires_at),
+            used_at: Set(change.used_at),
+            created_at: Set(change.created_at),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl PendingEmailChangeRepository for SeaOrmPendingEmailChangeRepository {
+    async fn save(&self, change: PendingEmailChange) -> anyhow::Result<PendingEmailChange> {
+        let saved_change = self
+            .retry
+            .run_write("save pending email change", || {
+                pending_email_changes::Entity::insert(Self::change_to_active_model(change.clone()))
+                    .exec_with_returning(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(Self::model_to_change(saved_change))
+    }
+
+    async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> anyhow::Result<Option<PendingEmailChange>> {
+        let found_change = self
+            .retry
+            .run_read("find pending email change", || {
+                pending_email_changes::Entity::find()
+                    .filter(pending_email_changes::Column::TokenHash.eq(token_hash))
+                    .one(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
+            .map(Self::model_to_change);
+        Ok(found_change)
+    }
+
+    async fn mark_used(&self, id: &str, used_at: DateTimeUtc) -> anyhow::Result<bool> {
+        let result = self
+            .retry
+            .run_write("mark pending email change used", || {
+                pending_email_changes::Entity::update_many()
+                    .col_expr(pending_email_changes::Column::UsedAt, Expr::value(used_at))
+                    .filter(pending_email_changes::Column::Id.eq(id))
+                    .filter(pending_email_changes::Column::UsedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(result.rows_affected == 1)
+    }
+
+    async fn invalidate_for_user(&self, user_id: &str, at: DateTimeUtc) -> anyhow::Result<()> {
+        self.retry
+            .run_write("invalidate pending email changes", || {
+                pending_email_changes::Entity::update_many()
+                    .col_expr(pending_email_changes::Column::UsedAt, Expr::value(at))
+                    .filter(pending_email_changes::Column::UserId.eq(user_id))
+                    .filter(pending_email_changes::Column::UsedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::auth::spi::refresh_token_repository::RefreshTokenRepository;
+use crate::domain::common::DateTimeUtc;
+use crate::domain::refresh_token::RefreshToken;
+use crate::infrastructure::persistence::seaorm::entity::refresh_tokens;
+use crate::infrastructure::persistence::seaorm::errors::map_db_error;
+use crate::infrastructure::persistence::seaorm::retry::DbRetryPolicy;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+pub struct SeaOrmRefreshTokenRepository {
+    pub db: DatabaseConnection,
+    pub retry: DbRetryPolicy,
+}
+
+impl SeaOrmRefreshTokenRepository {
+    fn model_to_token(model: refresh_tokens::Model) -> RefreshToken {
+        RefreshToken {
+            id: model.id,
+            user_id: model.user_id,
+            family_id: model.family_id,
+            token_hash: model.token_hash,
+            expires_at: model.expires_at,
+            revoked_at: model.revoked_at,
+            created_at: model.created_at,
+        }
+    }
+
+    fn token_to_active_model(token: RefreshToken) -> refresh_tokens::ActiveModel {
+        refresh_tokens::ActiveModel {
+            id: Set(token.id),
+            user_id: Set(token.user_id),
+            family_id: Set(token.family_id),
+            token_hash: Set(token.token_hash),
+            expires_at: Set(token.expires_at),
+            revoked_at: Set(token.revoked_at),
+            created_at: Set(token.created_at),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl RefreshTokenRepository for SeaOrmRefreshTokenRepository {
+    async fn save(&self, token: RefreshToken) -> anyhow::Result<RefreshToken> {
+        let saved_token = self
+            .retry
+            .run_write("save refresh token", || {
+                refresh_tokens::Entity::insert(Self::token_to_active_model(token.clone()))
+                    .exec_with_returning(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(Self::model_to_token(saved_token))
+    }
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> anyhow::Result<Option<RefreshToken>> {
+        let found_token = self
+            .retry
+            .run_read("find refresh token", || {
+                refresh_tokens::Entity::find()
+                    .filter(refresh_tokens::Column::TokenHash.eq(token_hash))
+                    .one(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
+            .map(Self::model_to_token);
+        Ok(found_token)
+    }
+
+    async fn revoke(&self, id: &str, revoked_at: DateTimeUtc) -> anyhow::Result<bool> {
+        let result = self
+            .retry
+            .run_write("revoke refresh token", || {
+                refresh_tokens::Entity::update_many()
+                    .col_expr(refresh_tokens::Column::RevokedAt, Expr::value(revoked_at))
+                    .filter(refresh_tokens::Column::Id.eq(id))
+                    .filter(refresh_tokens::Column::RevokedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(result.rows_affected == 1)
+    }
+
+    async fn revoke_family(&self, family_id: &str, revoked_at: DateTimeUtc) -> anyhow::Result<()> {
+        self.retry
+            .run_write("revoke refresh token family", || {
+                refresh_tokens::Entity::update_many()
+                    .col_expr(refresh_tokens::Column::RevokedAt, Expr::value(revoked_at))
+                    .filter(refresh_tokens::Column::FamilyId.eq(family_id))
+                    .filter(refresh_tokens::Column::RevokedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
+
+    async fn revoke_for_user(&self, user_id: &str, revoked_at: DateTimeUtc) -> anyhow::Result<()> {
+        self.retry
+            .run_write("revoke refresh tokens of user", || {
+                refresh_tokens::Entity::update_many()
+                    .col_expr(refresh_tokens::Column::RevokedAt, Expr::value(revoked_at))
+                    .filter(refresh_tokens::Column::UserId.eq(user_id))
+                    .filter(refresh_tokens::Column::RevokedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
+}
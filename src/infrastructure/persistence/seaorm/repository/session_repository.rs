@@ -0,0 +1,105 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::session::spi::session_repository::SessionRepository;
+use crate::domain::session::SessionMetadata;
+use crate::infrastructure::persistence::seaorm::entity::user_sessions;
+use crate::infrastructure::persistence::seaorm::errors::map_db_error;
+use crate::infrastructure::persistence::seaorm::retry::DbRetryPolicy;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set};
+
+pub struct SeaOrmSessionRepository {
+    pub db: DatabaseConnection,
+    pub retry: DbRetryPolicy,
+}
+
+impl SeaOrmSessionRepository {
+    fn model_to_session(model: user_sessions::Model) -> SessionMetadata {
+        SessionMetadata {
+            id: model.id,
+            user_id: model.user_id,
+            user_agent: model.user_agent,
+            ip_address: model.ip_address,
+            created_at: model.created_at,
+            last_seen_at: model.last_seen_at,
+        }
+    }
+
+    fn session_to_active_model(session: SessionMetadata) -> user_sessions::ActiveModel {
+        user_sessions::ActiveModel {
+            id: Set(session.id),
+            user_id: Set(session.user_id),
+            user_agent: Set(session.user_agent),
+            ip_address: Set(session.ip_address),
+            created_at: Set(session.created_at),
+            last_seen_at: Set(session.last_seen_at),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionRepository for SeaOrmSessionRepository {
+    async fn save(&self, session: SessionMetadata) -> anyhow::Result<SessionMetadata> {
+        let saved_session = self
+            .retry
+            .run_write("save session", || {
+                user_sessions::Entity::insert(Self::session_to_active_model(session.clone()))
+                    .on_conflict(
+                        OnConflict::column(user_sessions::Column::Id)
+                            .update_columns([
+                                user_sessions::Column::UserId,
+                                user_sessions::Column::UserAgent,
+                                user_sessions::Column::IpAddress,
+                                user_sessions::Column::LastSeenAt,
+                            ])
+                            .to_owned(),
+                    )
+                    .exec_with_returning(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+
+        Ok(Self::model_to_session(saved_session))
+    }
+
+    async fn find_by_user_id(&self, user_id: &str) -> anyhow::Result<Vec<SessionMetadata>> {
+        let sessions = self
+            .retry
+            .run_read("find sessions by user id", || {
+                user_sessions::Entity::find()
+                    .filter(user_sessions::Column::UserId.eq(user_id))
+                    .order_by_desc(user_sessions::Column::LastSeenAt)
+                    .all(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
+            .into_iter()
+            .map(Self::model_to_session)
+            .collect();
+        Ok(sessions)
+    }
+
+    async fn delete(&self, id: &str) -> anyhow::Result<()> {
+        self.retry
+            .run_write("delete session", || {
+                user_sessions::Entity::delete_by_id(id.to_string()).exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
+}
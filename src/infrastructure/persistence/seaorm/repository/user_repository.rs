@@ -14,13 +14,21 @@
  * limitations under the License.
  */
 use crate::application::user::spi::user_repository::UserRepository;
+use crate::domain::common::DateTimeUtc;
 use crate::domain::user::User;
 use crate::infrastructure::persistence::seaorm::entity::users;
+use crate::infrastructure::persistence::seaorm::errors::{map_db_error, unique_violation};
+use crate::infrastructure::persistence::seaorm::retry::DbRetryPolicy;
 use sea_orm::ColumnTrait;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use sea_orm::sea_query::{Expr, ExprTrait};
+use sea_orm::{DatabaseConnection, DbErr, EntityTrait, NotSet, PaginatorTrait, QueryFilter, Set};
 
+const UNIQUE_CONSTRAINTS: [(&str, &str); 1] = [("users_email_key", "email")];
+
+/// Reads and writes `db`. Bound to a replica, only the finders work.
 pub struct SeaOrmUserRepository {
     pub db: DatabaseConnection,
+    pub retry: DbRetryPolicy,
 }
 
 impl SeaOrmUserRepository {
@@ -31,6 +39,35 @@ impl SeaOrmUserRepository {
             password: model.password,
             created_at: model.created_at,
             updated_at: model.updated_at,
+            locale: model.locale,
+            timezone: model.timezone,
+            email_verified_at: model.email_verified_at,
+            failed_login_attempts: model.failed_login_attempts,
+            locked_until: model.locked_until,
+            deleted_at: model.deleted_at,
+        }
+    }
+
+    fn map_write_error(error: sea_orm::DbErr) -> anyhow::Error {
+        match unique_violation(&error, &UNIQUE_CONSTRAINTS) {
+            Some(violation) => violation.into(),
+            None => map_db_error(error),
+        }
+    }
+
+    fn user_to_active_model(user: User) -> users::ActiveModel {
+        users::ActiveModel {
+            id: Set(user.id),
+            email: Set(user.email),
+            password: Set(user.password),
+            created_at: Set(user.created_at),
+            updated_at: Set(user.updated_at),
+            locale: Set(user.locale),
+            timezone: Set(user.timezone),
+            email_verified_at: Set(user.email_verified_at),
+            failed_login_attempts: Set(user.failed_login_attempts),
+            locked_until: Set(user.locked_until),
+            deleted_at: Set(user.deleted_at),
         }
     }
 }
@@ -38,50 +75,205 @@ impl SeaOrmUserRepository {
 #[async_trait::async_trait]
 impl UserRepository for SeaOrmUserRepository {
     async fn find_by_email(&self, email: &str) -> anyhow::Result<Option<User>> {
-        let found_user = users::Entity::find()
-            .filter(users::Column::Email.eq(email))
-            .one(&self.db)
-            .await?
+        let found_user = self
+            .retry
+            .run_read("find user by email", || {
+                users::Entity::find()
+                    .filter(users::Column::Email.eq(email))
+                    .filter(users::Column::DeletedAt.is_null())
+                    .one(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
             .map(Self::model_to_user);
         Ok(found_user)
     }
 
     async fn find_by_id(&self, id: &str) -> anyhow::Result<Option<User>> {
-        let found_user = users::Entity::find()
-            .filter(users::Column::Id.eq(id))
-            .one(&self.db)
-            .await?
+        let found_user = self
+            .retry
+            .run_read("find user by id", || {
+                users::Entity::find()
+                    .filter(users::Column::Id.eq(id))
+                    .filter(users::Column::DeletedAt.is_null())
+                    .one(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
             .map(Self::model_to_user);
         Ok(found_user)
     }
 
-    async fn save(&self, user: User) -> anyhow::Result<User> {
-        let model = users::ActiveModel {
-            id: Set(user.id),
-            email: Set(user.email),
-            password: Set(user.password),
-            created_at: Set(user.created_at),
-            updated_at: Set(user.updated_at),
-        };
+    async fn find_by_email_including_deleted(&self, email: &str) -> anyhow::Result<Option<User>> {
+        let found_user = self
+            .retry
+            .run_read("find user by email including deleted", || {
+                users::Entity::find()
+                    .filter(users::Column::Email.eq(email))
+                    .one(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
+            .map(Self::model_to_user);
+        Ok(found_user)
+    }
 
-        let saved_user = users::Entity::insert(model)
-            .exec_with_returning(&self.db)
-            .await?;
+    async fn find_by_id_including_deleted(&self, id: &str) -> anyhow::Result<Option<User>> {
+        let found_user = self
+            .retry
+            .run_read("find user by id including deleted", || {
+                users::Entity::find()
+                    .filter(users::Column::Id.eq(id))
+                    .one(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?
+            .map(Self::model_to_user);
+        Ok(found_user)
+    }
+
+    async fn save(&self, user: User) -> anyhow::Result<User> {
+        let saved_user = self
+            .retry
+            .run_write("save user", || {
+                users::Entity::insert(Self::user_to_active_model(user.clone()))
+                    .exec_with_returning(&self.db)
+            })
+            .await
+            .map_err(Self::map_write_error)?;
 
         Ok(Self::model_to_user(saved_user))
     }
 
-    async fn update(&self, user: User) -> anyhow::Result<User> {
+    async fn update(&self, user: User) -> anyhow::Result<Option<User>> {
+        // The lockout and deletion columns change only through their targeted
+        // updates, so a concurrent failed login or deletion is not overwritten
+        // with a stale value.
         let model = users::ActiveModel {
-            id: Set(user.id.clone()),
-            email: Set(user.email),
-            password: Set(user.password),
-            created_at: Set(user.created_at),
-            updated_at: Set(user.updated_at),
+            failed_login_attempts: NotSet,
+            locked_until: NotSet,
+            deleted_at: NotSet,
+            ..Self::user_to_active_model(user)
         };
+        let statement = users::Entity::update(model)
+            .validate()
+            .map_err(map_db_error)?
+            .filter(users::Column::DeletedAt.is_null());
+        let updated_user = self
+            .retry
+            .run_write("update user", || statement.clone().exec(&self.db))
+            .await;
+
+        match updated_user {
+            Ok(updated_user) => Ok(Some(Self::model_to_user(updated_user))),
+            Err(DbErr::RecordNotUpdated) => Ok(None),
+            Err(e) => Err(Self::map_write_error(e)),
+        }
+    }
 
-        let updated_user = model.update(&self.db).await?;
+    async fn replace_password_hash(
+        &self,
+        id: &str,
+        current_hash: &str,
+        new_hash: &str,
+    ) -> anyhow::Result<bool> {
+        let result = self
+            .retry
+            .run_write("replace password hash", || {
+                users::Entity::update_many()
+                    .col_expr(users::Column::Password, Expr::value(new_hash))
+                    .filter(users::Column::Id.eq(id))
+                    .filter(users::Column::Password.eq(current_hash))
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(result.rows_affected > 0)
+    }
+
+    async fn increment_failed_logins(&self, id: &str) -> anyhow::Result<Option<i32>> {
+        let updated_users = self
+            .retry
+            .run_write("increment failed logins", || {
+                users::Entity::update_many()
+                    .col_expr(
+                        users::Column::FailedLoginAttempts,
+                        Expr::col(users::Column::FailedLoginAttempts).add(1),
+                    )
+                    .filter(users::Column::Id.eq(id))
+                    .exec_with_returning(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(updated_users
+            .into_iter()
+            .next()
+            .map(|user| user.failed_login_attempts))
+    }
+
+    async fn lock_until(&self, id: &str, until: DateTimeUtc) -> anyhow::Result<()> {
+        self.retry
+            .run_write("lock user", || {
+                users::Entity::update_many()
+                    .col_expr(users::Column::LockedUntil, Expr::value(until))
+                    .filter(users::Column::Id.eq(id))
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
+
+    async fn clear_failed_logins(&self, id: &str) -> anyhow::Result<()> {
+        self.retry
+            .run_write("clear failed logins", || {
+                users::Entity::update_many()
+                    .col_expr(users::Column::FailedLoginAttempts, Expr::value(0))
+                    .col_expr(
+                        users::Column::LockedUntil,
+                        Expr::value(Option::<DateTimeUtc>::None),
+                    )
+                    .filter(users::Column::Id.eq(id))
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
+
+    async fn soft_delete(&self, id: &str, at: DateTimeUtc) -> anyhow::Result<()> {
+        self.retry
+            .run_write("soft delete user", || {
+                users::Entity::update_many()
+                    .col_expr(users::Column::DeletedAt, Expr::value(at))
+                    .col_expr(users::Column::UpdatedAt, Expr::value(at))
+                    .filter(users::Column::Id.eq(id))
+                    .filter(users::Column::DeletedAt.is_null())
+                    .exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: &str) -> anyhow::Result<()> {
+        self.retry
+            .run_write("delete user", || {
+                users::Entity::delete_by_id(id).exec(&self.db)
+            })
+            .await
+            .map_err(map_db_error)?;
+        Ok(())
+    }
 
-        Ok(Self::model_to_user(updated_user))
+    async fn count(&self) -> anyhow::Result<u64> {
+        self.retry
+            .run_read("count users", || {
+                users::Entity::find()
+                    .filter(users::Column::DeletedAt.is_null())
+                    .count(&self.db)
+            })
+            .await
+            .map_err(map_db_error)
     }
 }
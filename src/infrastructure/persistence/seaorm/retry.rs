@@ -0,0 +1,248 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::env_parse;
+use crate::infrastructure::deadline::current_deadline;
+use sea_orm::{DbErr, RuntimeErr, SqlxError};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+
+const SERIALIZATION_FAILURE: &str = "40001";
+const DEADLOCK_DETECTED: &str = "40P01";
+
+#[derive(Clone, Copy, Debug)]
+pub struct DbRetryPolicy {
+    pub max_retries: u32,
+    pub backoff: Duration,
+}
+
+impl DbRetryPolicy {
+    pub fn from_env() -> anyhow::Result<Self> {
+        Ok(Self {
+            max_retries: env_parse("DB_MAX_RETRIES", 3)?,
+            backoff: Duration::from_millis(env_parse("DB_RETRY_BACKOFF_MS", 50)?),
+        })
+    }
+
+    /// Runs a read, retrying any transient error.
+    pub async fn run_read<T, F, Fut>(&self, operation: &str, f: F) -> Result<T, DbErr>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, DbErr>>,
+    {
+        self.run(operation, f, is_transient).await
+    }
+
+    /// Runs a write, retrying only errors raised before the statement was
+    /// sent. A write that failed afterwards may have been applied, and
+    /// repeating it could apply it twice.
+    pub async fn run_write<T, F, Fut>(&self, operation: &str, f: F) -> Result<T, DbErr>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, DbErr>>,
+    {
+        self.run(operation, f, is_unsent).await
+    }
+
+    async fn run<T, F, Fut>(
+        &self,
+        operation: &str,
+        mut f: F,
+        is_retryable: fn(&DbErr) -> bool,
+    ) -> Result<T, DbErr>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, DbErr>>,
+    {
+        let deadline = current_deadline();
+        let mut attempt = 0;
+        loop {
+            let result = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline, f())
+                    .await
+                    .unwrap_or_else(|_| Err(deadline_exceeded(operation))),
+                None => f().await,
+            };
+            match result {
+                Err(e) if attempt < self.max_retries && is_retryable(&e) => {
+                    attempt += 1;
+                    tracing::debug!(
+                        "Retrying {} after transient database error (attempt {}/{}): {}",
+                        operation,
+                        attempt,
+                        self.max_retries,
+                        e
+                    );
+                    let delay = self.backoff.saturating_mul(1 << (attempt - 1).min(10));
+                    if deadline.is_some_and(|deadline| Instant::now() + delay >= deadline) {
+                        return Err(deadline_exceeded(operation));
+                    }
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+fn deadline_exceeded(operation: &str) -> DbErr {
+    tracing::warn!("Abandoned {} after the request deadline passed", operation);
+    DbErr::Custom(format!("{} exceeded the request deadline", operation))
+}
+
+pub fn is_transient(error: &DbErr) -> bool {
+    is_connection_error(error) || is_retryable_conflict(error)
+}
+
+/// No connection could be checked out of the pool, so nothing was sent.
+fn is_unsent(error: &DbErr) -> bool {
+    match error {
+        DbErr::ConnectionAcquire(_) => true,
+        DbErr::Exec(RuntimeErr::SqlxError(e)) | DbErr::Query(RuntimeErr::SqlxError(e)) => {
+            matches!(e.as_ref(), SqlxError::PoolTimedOut | SqlxError::PoolClosed)
+        }
+        _ => false,
+    }
+}
+
+/// The database could not be reached or the pool gave up, as opposed to a
+/// statement that reached the database and failed.
+pub fn is_connection_error(error: &DbErr) -> bool {
+    match error {
+        DbErr::ConnectionAcquire(_) | DbErr::Conn(_) => true,
+        DbErr::Exec(RuntimeErr::SqlxError(e)) | DbErr::Query(RuntimeErr::SqlxError(e)) => {
+            matches!(
+                e.as_ref(),
+                SqlxError::Io(_)
+                    | SqlxError::PoolTimedOut
+                    | SqlxError::PoolClosed
+                    | SqlxError::WorkerCrashed
+            )
+        }
+        _ => false,
+    }
+}
+
+fn is_retryable_conflict(error: &DbErr) -> bool {
+    match error {
+        DbErr::Exec(RuntimeErr::SqlxError(e)) | DbErr::Query(RuntimeErr::SqlxError(e)) => {
+            match e.as_ref() {
+                SqlxError::Database(e) => matches!(
+                    e.code().as_deref(),
+                    Some(SERIALIZATION_FAILURE | DEADLOCK_DETECTED)
+                ),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::ConnAcquireErr;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy() -> DbRetryPolicy {
+        DbRetryPolicy {
+            max_retries: 3,
+            backoff: Duration::from_millis(10),
+        }
+    }
+
+    fn io_error() -> DbErr {
+        let error = std::io::Error::from(std::io::ErrorKind::ConnectionReset);
+        DbErr::Query(RuntimeErr::SqlxError(SqlxError::Io(error).into()))
+    }
+
+    /// Fails with `error` on the first `failures` calls, then succeeds.
+    async fn flaky(calls: &AtomicU32, failures: u32, error: fn() -> DbErr) -> Result<u32, DbErr> {
+        let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+        if call <= failures {
+            Err(error())
+        } else {
+            Ok(call)
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn read_retries_transient_error_until_it_succeeds() {
+        let calls = AtomicU32::new(0);
+
+        let result = policy()
+            .run_read("find", || flaky(&calls, 2, io_error))
+            .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn read_gives_up_after_max_retries() {
+        let calls = AtomicU32::new(0);
+
+        let result = policy()
+            .run_read("find", || flaky(&calls, u32::MAX, io_error))
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn write_does_not_retry_error_after_statement_was_sent() {
+        let calls = AtomicU32::new(0);
+
+        let result = policy()
+            .run_write("save", || flaky(&calls, 1, io_error))
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn write_retries_connection_acquire_failure() {
+        let calls = AtomicU32::new(0);
+
+        let result = policy()
+            .run_write("save", || {
+                flaky(&calls, 2, || {
+                    DbErr::ConnectionAcquire(ConnAcquireErr::Timeout)
+                })
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn non_transient_error_is_not_retried() {
+        let calls = AtomicU32::new(0);
+
+        let result = policy()
+            .run_read("find", || {
+                flaky(&calls, 1, || DbErr::RecordNotFound("user".to_string()))
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,69 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::application::auth::spi::breached_password_checker::BreachedPasswordChecker;
+use crate::infrastructure::config::{env_or, env_parse};
+use sha1::{Digest, Sha1};
+use std::time::Duration;
+
+/// HaveIBeenPwned range API client. Only the first five hex characters of
+/// the SHA-1 hash leave the process (k-anonymity).
+pub struct PwnedPasswordsClient {
+    pub client: reqwest::Client,
+    pub base_url: String,
+    pub timeout: Duration,
+}
+
+impl PwnedPasswordsClient {
+    pub fn from_env(client: reqwest::Client) -> anyhow::Result<Self> {
+        Ok(Self {
+            client,
+            base_url: env_or("PWNED_PASSWORDS_API_URL", "https://api.pwnedpasswords.com"),
+            timeout: Duration::from_millis(env_parse("PWNED_PASSWORDS_TIMEOUT_MS", 2000)?),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl BreachedPasswordChecker for PwnedPasswordsClient {
+    async fn breach_count(&self, password: &str) -> anyhow::Result<u64> {
+        let digest = format!("{:X}", Sha1::digest(password.as_bytes()));
+        let (prefix, suffix) = digest.split_at(5);
+
+        let body = self
+            .client
+            .get(format!(
+                "{}/range/{}",
+                self.base_url.trim_end_matches('/'),
+                prefix
+            ))
+            .header("Add-Padding", "true")
+            .timeout(self.timeout)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let count = body
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+            .and_then(|(_, count)| count.parse().ok())
+            .unwrap_or(0);
+        Ok(count)
+    }
+}
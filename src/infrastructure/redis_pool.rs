@@ -0,0 +1,66 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::env_parse;
+use socket2::TcpKeepalive;
+use std::time::Duration;
+use tower_sessions_redis_store::fred::prelude::*;
+use tower_sessions_redis_store::fred::types::config::{ConnectionConfig, ReconnectPolicy};
+
+const POOL_SIZE: usize = 6;
+
+/// Connects the Redis pool with connection recycling: TCP keepalive so idle
+/// sockets dropped by middleboxes are detected, reconnection with backoff, and
+/// an optional maximum connection lifetime after which every connection is
+/// re-established.
+pub async fn connect_redis_pool(redis_url: &str) -> anyhow::Result<Pool> {
+    let config = Config::from_url(redis_url)?;
+    let mut connection = ConnectionConfig {
+        max_command_attempts: 3,
+        ..Default::default()
+    };
+    let keepalive_secs: u64 = env_parse("REDIS_TCP_KEEPALIVE_SECS", 60)?;
+    if keepalive_secs > 0 {
+        let keepalive = TcpKeepalive::new().with_time(Duration::from_secs(keepalive_secs));
+        connection.tcp.keepalive = Some(keepalive);
+    }
+    let policy = ReconnectPolicy::new_exponential(0, 100, 30_000, 2);
+
+    let pool = Pool::new(config, None, Some(connection), Some(policy), POOL_SIZE)?;
+    pool.connect();
+    pool.wait_for_connect().await?;
+
+    let max_lifetime_secs: u64 = env_parse("REDIS_MAX_CONNECTION_LIFETIME_SECS", 3600)?;
+    if max_lifetime_secs > 0 {
+        spawn_connection_recycler(pool.clone(), Duration::from_secs(max_lifetime_secs));
+    }
+    Ok(pool)
+}
+
+fn spawn_connection_recycler(pool: Pool, max_lifetime: Duration) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(max_lifetime);
+        // The first tick completes immediately, right after connecting.
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            tracing::debug!("Recycling Redis connections");
+            if let Err(e) = pool.force_reconnection().await {
+                tracing::warn!("Failed to recycle Redis connections: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,61 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use tower_sessions::SessionStore;
+use tower_sessions::session::{Id, Record};
+use tower_sessions::session_store::{Error, Result};
+
+/// Treats a session record that cannot be decoded (left behind by an
+/// incompatible deploy or a rotated-out encryption key) as missing, so the
+/// request proceeds with a fresh session instead of failing with a 500.
+#[derive(Clone, Debug)]
+pub struct ResilientSessionStore<S> {
+    inner: S,
+}
+
+impl<S> ResilientSessionStore<S> {
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SessionStore> SessionStore for ResilientSessionStore<S> {
+    async fn create(&self, record: &mut Record) -> Result<()> {
+        self.inner.create(record).await
+    }
+
+    async fn save(&self, record: &Record) -> Result<()> {
+        self.inner.save(record).await
+    }
+
+    async fn load(&self, session_id: &Id) -> Result<Option<Record>> {
+        match self.inner.load(session_id).await {
+            Err(Error::Decode(e)) => {
+                tracing::debug!("Discarding undecodable session record: {}", e);
+                if let Err(e) = self.inner.delete(session_id).await {
+                    tracing::debug!("Failed to delete undecodable session record: {}", e);
+                }
+                Ok(None)
+            }
+            result => result,
+        }
+    }
+
+    async fn delete(&self, session_id: &Id) -> Result<()> {
+        self.inner.delete(session_id).await
+    }
+}
@@ -13,90 +13,368 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-use crate::infrastructure::app_state::AppState;
+use crate::infrastructure::app_state::{AppSessionStore, AppState};
+use crate::infrastructure::config::{
+    env_flag, env_or, env_or_file, env_parse, env_var, is_declared_production, is_production,
+};
+use crate::infrastructure::http::common::auth::AuthenticatedUser;
+use crate::infrastructure::http::common::canonical_host::{canonical_host, CanonicalHostConfig};
+use crate::infrastructure::http::common::content_type::json_charset;
+use crate::infrastructure::http::common::cors::CorsConfig;
+use crate::infrastructure::http::common::deprecation::{
+    deprecation_headers, mark_deprecated_operations,
+};
+use crate::infrastructure::http::common::latency_budget::{
+    latency_budget, record_matched_path, LatencyBudgetConfig,
+};
+use crate::infrastructure::http::common::request_id::{
+    scope_request_id, MakeRequestIdFromTrace, RequestIdConfig,
+};
+use crate::infrastructure::http::common::security_headers::{
+    docs_content_security_policy, security_headers, SecurityHeadersConfig,
+};
+use crate::infrastructure::http::common::session_expiry::{
+    session_expiry_jitter, SessionExpiryJitter, SESSION_INACTIVITY,
+};
+use crate::infrastructure::http::common::timeout::{
+    handle_timeout_error, log_cancellation, propagate_deadline,
+};
+use crate::infrastructure::http::common::trailing_slash::{trailing_slash, TrailingSlashMode};
+use crate::infrastructure::http::common::validator::set_strict_json;
+use crate::infrastructure::http::error_handler::{
+    set_debug_errors, set_retry_after, ApiError, ErrorKind,
+};
 use crate::infrastructure::http::*;
 use crate::infrastructure::openapi::BaseOpenApi;
-use axum::Router;
-use std::env;
-use std::net::Ipv4Addr;
+use crate::infrastructure::redis_pool::connect_redis_pool;
+use crate::infrastructure::resilient_session_store::ResilientSessionStore;
+use crate::infrastructure::session_encryption::{EncryptingSessionStore, SessionCipher};
+use axum::body::Body;
+use axum::error_handling::HandleErrorLayer;
+use axum::http::Request;
+use axum::http::header::{AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION, SET_COOKIE};
+use axum::routing::get;
+use axum::{middleware, Json, Router};
+use std::net::{Ipv4Addr, SocketAddr};
 use std::sync::Arc;
 use std::time::Duration;
-use time::Duration as SessionDuration;
 use tokio::net::TcpListener;
+use tokio::runtime::{self, Runtime};
+use tower::timeout::TimeoutLayer;
 use tower::ServiceBuilder;
 use tower_http::compression::CompressionLayer;
-use tower_http::cors::CorsLayer;
 use tower_http::decompression::RequestDecompressionLayer;
-use tower_http::timeout::TimeoutLayer;
-use tower_http::trace::TraceLayer;
+use tower_http::request_id::{PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::sensitive_headers::{
+    SetSensitiveRequestHeadersLayer, SetSensitiveResponseHeadersLayer,
+};
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
 use tower_http::CompressionLevel;
 use tower_sessions::cookie::SameSite;
 use tower_sessions::{Expiry, SessionManagerLayer};
-use tower_sessions_redis_store::{fred::prelude::*, RedisStore};
+use tower_sessions_redis_store::fred::prelude::Pool;
+use tower_sessions_redis_store::RedisStore;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::registry;
 use tracing_subscriber::util::SubscriberInitExt;
 use utoipa::openapi::OpenApi;
+use utoipa_axum::router::OpenApiRouter;
 use utoipa_axum::routes;
 use utoipa_scalar::{Scalar, Servable};
 use utoipa_swagger_ui::SwaggerUi;
 
+const VERSIONED_OPENAPI_JSON_PATH: &str = "/api-docs/v1/openapi.json";
+
+/// Builds the multi-threaded runtime, with `WORKER_THREADS` workers when set
+/// so the server can match a container CPU quota instead of the host's cores.
+/// Call after the config file is loaded.
+pub fn build_runtime() -> anyhow::Result<Runtime> {
+    let mut builder = runtime::Builder::new_multi_thread();
+    if let Some(worker_threads) = env_var("WORKER_THREADS") {
+        let worker_threads: usize = worker_threads
+            .parse()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Invalid WORKER_THREADS environment variable: {:?} is not a positive integer",
+                    worker_threads
+                )
+            })?;
+        builder.worker_threads(worker_threads);
+    }
+    Ok(builder.enable_all().build()?)
+}
+
 pub async fn initialize_server() -> anyhow::Result<()> {
     init_observability();
+    set_strict_json(env_flag("STRICT_JSON", false)?);
+    set_retry_after(env_parse("DB_UNAVAILABLE_RETRY_AFTER_SECS", 5)?);
+    set_debug_errors(debug_errors_enabled()?);
     let port = get_server_port()?;
-    let app_state = Arc::new(AppState::initialize_app_state().await?);
-    let session_layer = initialize_session_layer().await?;
+    let redis_url =
+        env_or_file("REDIS_URL")?.unwrap_or_else(|| "redis://127.0.0.1:6379".to_string());
+    let redis_pool = connect_redis_pool(&redis_url).await?;
+    let session_store = connect_session_store(redis_pool.clone())?;
+    let session_cookie_name = session_cookie_name()?;
+    let required_session_cookie =
+        env_flag("SESSION_COOKIE_FAST_REJECT", false)?.then(|| session_cookie_name.clone());
+    let app_state = Arc::new(
+        AppState::initialize_app_state(session_store.clone(), redis_pool, required_session_cookie)
+            .await?,
+    );
+    let session_layer = initialize_session_layer(session_store, session_cookie_name)?;
+    let router_config = RouterConfig::from_env()?;
 
-    let router = setup_router(app_state.clone(), session_layer);
+    let router = setup_router(app_state.clone(), session_layer, router_config);
+    if let Some(management_port) = get_management_port()? {
+        spawn_management_server(app_state.clone(), management_port).await?;
+    }
     start_server(router, port).await?;
     Ok(())
 }
 
+// PORT_ENV_VARS lists the variables to try in order, for platforms that
+// expose the port under their own name.
 fn get_server_port() -> anyhow::Result<u16> {
-    env::var("PORT")
-        .unwrap_or_else(|_| "3000".to_string())
-        .parse()
-        .map_err(|e| anyhow::anyhow!("Invalid PORT environment variable: {}", e))
+    let names = env_or("PORT_ENV_VARS", "PORT");
+    let found = names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .find_map(|name| env_var(name).map(|value| (name, value)));
+
+    match found {
+        Some((name, value)) => value
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid {} environment variable: {}", name, e)),
+        None => Ok(3000),
+    }
+}
+
+fn get_management_port() -> anyhow::Result<Option<u16>> {
+    env_var("MANAGEMENT_PORT")
+        .map(|port| {
+            port.parse().map_err(|e| {
+                anyhow::anyhow!("Invalid MANAGEMENT_PORT environment variable: {}", e)
+            })
+        })
+        .transpose()
+}
+
+struct RouterConfig {
+    security_headers: Arc<SecurityHeadersConfig>,
+    request_id: RequestIdConfig,
+    cors: CorsConfig,
+    canonical_host: Arc<CanonicalHostConfig>,
+    request_timeout: Duration,
+    latency_budget: Arc<LatencyBudgetConfig>,
+    session_expiry_jitter: SessionExpiryJitter,
+    trace_include_headers: bool,
+    docs_enabled: bool,
+    openapi_versioned_url: bool,
+    openapi_json_path: Option<String>,
+    trailing_slash: TrailingSlashMode,
+}
+
+impl RouterConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        Ok(Self {
+            security_headers: Arc::new(SecurityHeadersConfig::from_env()?),
+            request_id: RequestIdConfig::from_env()?,
+            cors: CorsConfig::from_env()?,
+            canonical_host: Arc::new(CanonicalHostConfig::from_env()?),
+            request_timeout: Duration::from_secs(env_parse("REQUEST_TIMEOUT_SECS", 10)?),
+            latency_budget: Arc::new(LatencyBudgetConfig::from_env()?),
+            session_expiry_jitter: SessionExpiryJitter::from_env()?,
+            trace_include_headers: env_flag("TRACE_INCLUDE_HEADERS", false)?,
+            docs_enabled: env_flag("ENABLE_DOCS", cfg!(debug_assertions))?,
+            openapi_versioned_url: env_flag("OPENAPI_VERSIONED_URL", true)?,
+            openapi_json_path: env_var("OPENAPI_JSON_PATH"),
+            trailing_slash: env_parse("TRAILING_SLASH", TrailingSlashMode::Rewrite)?,
+        })
+    }
 }
 
 fn setup_router(
     app_state: Arc<AppState>,
-    session_layer: SessionManagerLayer<RedisStore<Pool>>,
+    session_layer: SessionManagerLayer<AppSessionStore>,
+    config: RouterConfig,
 ) -> Router {
-    let (router, api) = setup_routes_and_openapi();
-    let documentation_router = setup_documentation(api);
+    let (router, mut api) = setup_routes_and_openapi(&config.cors);
+    mark_deprecated_operations(&mut api);
+    let api = versioned_openapi(api);
+    let documentation_router = if config.docs_enabled {
+        setup_documentation(api, config.openapi_versioned_url)
+    } else if let Some(path) = &config.openapi_json_path {
+        setup_protected_openapi_json(path, api)
+    } else {
+        Router::new()
+    };
+    let documentation_router = documentation_router
+        .layer(config.cors.public_layer())
+        .layer(middleware::from_fn_with_state(
+            config.security_headers.clone(),
+            docs_content_security_policy,
+        ));
+    let request_id_config = config.request_id;
+    let trace_include_headers = config.trace_include_headers;
 
-    router
+    let router = router
         .merge(documentation_router)
-        .with_state(app_state)
+        .route_layer(middleware::from_fn(record_matched_path))
+        .route_layer(middleware::from_fn(deprecation_headers))
+        .with_state(app_state);
+
+    Router::new()
+        .fallback_service(router)
+        .layer(middleware::from_fn_with_state(
+            config.trailing_slash,
+            trailing_slash,
+        ))
+        .layer(middleware::from_fn_with_state(
+            config.canonical_host,
+            canonical_host,
+        ))
+        .layer(middleware::from_fn(json_charset))
+        .layer(middleware::from_fn_with_state(
+            config.session_expiry_jitter,
+            session_expiry_jitter,
+        ))
         .layer(session_layer)
         .layer(
             ServiceBuilder::new()
                 .layer(RequestDecompressionLayer::new())
                 .layer(CompressionLayer::new().quality(CompressionLevel::Fastest)),
         )
-        .layer(CorsLayer::permissive())
+        .layer(middleware::from_fn_with_state(
+            config.security_headers,
+            security_headers,
+        ))
         .layer((
-            TraceLayer::new_for_http(),
-            TimeoutLayer::new(Duration::from_secs(10)),
+            // Credentials are marked sensitive before tracing sees the
+            // request and before it sees the response, so they print as
+            // "Sensitive" whatever the log level.
+            SetSensitiveRequestHeadersLayer::new([COOKIE, AUTHORIZATION, PROXY_AUTHORIZATION]),
+            SetRequestIdLayer::new(
+                request_id_config.header_name.clone(),
+                MakeRequestIdFromTrace::new(&request_id_config),
+            ),
+            PropagateRequestIdLayer::new(request_id_config.header_name.clone()),
+            middleware::from_fn(scope_request_id),
+            TraceLayer::new_for_http()
+                .make_span_with(move |request: &Request<Body>| {
+                    let request_id = request
+                        .headers()
+                        .get(&request_id_config.header_name)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or_default();
+                    let span = tracing::debug_span!(
+                        "request",
+                        method = %request.method(),
+                        uri = %request.uri(),
+                        version = ?request.version(),
+                        request_id = %request_id,
+                        headers = tracing::field::Empty,
+                    );
+                    if trace_include_headers {
+                        span.record("headers", tracing::field::debug(request.headers()));
+                    }
+                    span
+                })
+                .on_response(DefaultOnResponse::new().include_headers(trace_include_headers)),
+            middleware::from_fn_with_state(config.latency_budget, latency_budget),
+            SetSensitiveResponseHeadersLayer::new([SET_COOKIE]),
+            middleware::from_fn(log_cancellation),
+            HandleErrorLayer::new(handle_timeout_error),
+            TimeoutLayer::new(config.request_timeout),
+            middleware::from_fn_with_state(config.request_timeout, propagate_deadline),
         ))
 }
 
-fn setup_routes_and_openapi() -> (Router<Arc<AppState>>, OpenApi) {
-    BaseOpenApi::router::<Arc<AppState>>()
+// Routes are grouped so each group gets its own CORS policy: public routes are
+// open to any origin, authenticated routes only to the configured origins.
+fn setup_routes_and_openapi(cors: &CorsConfig) -> (Router<Arc<AppState>>, OpenApi) {
+    let public_routes = OpenApiRouter::new()
         .routes(routes!(health_handler::health_check))
+        .layer(cors.public_layer());
+
+    let auth_routes = OpenApiRouter::new()
         .routes(routes!(auth_handler::register))
+        .routes(routes!(auth_handler::validate_register))
+        .routes(routes!(auth_handler::check_pwned_password))
         .routes(routes!(auth_handler::login))
         .routes(routes!(auth_handler::logout))
-        .routes(routes!(auth_handler::get_profile))
+        .routes(routes!(auth_handler::logout_all))
+        .routes(routes!(auth_handler::list_sessions))
+        .routes(routes!(auth_handler::revoke_session))
+        .routes(routes!(auth_handler::get_profile, auth_handler::update_profile))
         .routes(routes!(auth_handler::change_password))
+        .routes(routes!(auth_handler::change_email))
+        .routes(routes!(auth_handler::confirm_email_change))
+        .routes(routes!(auth_handler::delete_account))
+        .routes(routes!(auth_handler::forgot_password))
+        .routes(routes!(auth_handler::reset_password))
+        .routes(routes!(auth_handler::request_magic_link))
+        .routes(routes!(auth_handler::verify_magic_link))
+        .routes(routes!(auth_handler::verify_email))
+        .routes(routes!(auth_handler::refresh_token))
+        .routes(routes!(auth_handler::revoke_tokens))
+        .routes(routes!(auth_handler::oauth_authorize))
+        .routes(routes!(auth_handler::oauth_callback))
+        .layer(cors.authenticated_layer());
+
+    BaseOpenApi::router::<Arc<AppState>>()
+        .merge(public_routes)
+        .merge(auth_routes)
         .split_for_parts()
 }
 
-fn setup_documentation(api: OpenApi) -> Router<Arc<AppState>> {
-    Router::new()
+// Tooling keys on `info.version` to detect spec changes, so it always tracks
+// the crate version.
+fn versioned_openapi(mut api: OpenApi) -> OpenApi {
+    api.info.version = env!("CARGO_PKG_VERSION").to_string();
+    api
+}
+
+fn setup_documentation(api: OpenApi, versioned_url: bool) -> Router<Arc<AppState>> {
+    // The UIs serialize the spec while building their routes; checking it up
+    // front lets a broken spec degrade the docs instead of the server.
+    if let Err(e) = api.to_json() {
+        tracing::error!("Failed to generate the OpenAPI spec, docs are unavailable: {}", e);
+        return setup_unavailable_documentation();
+    }
+    let mut router = Router::new()
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", api.clone()))
-        .merge(Scalar::with_url("/scalar", api))
+        .merge(Scalar::with_url("/scalar", api.clone()));
+    if versioned_url {
+        router = router.route(VERSIONED_OPENAPI_JSON_PATH, get(move || async move { Json(api) }));
+    }
+    router
+}
+
+fn setup_unavailable_documentation() -> Router<Arc<AppState>> {
+    let unavailable = || async {
+        ApiError::new(
+            "documentation_unavailable_error".to_string(),
+            ErrorKind::ServiceUnavailable,
+        )
+    };
+    Router::new()
+        .route("/swagger-ui", get(unavailable))
+        .route("/swagger-ui/{*rest}", get(unavailable))
+        .route("/scalar", get(unavailable))
+        .route("/api-docs/openapi.json", get(unavailable))
+        .route(VERSIONED_OPENAPI_JSON_PATH, get(unavailable))
+}
+
+// With the documentation UI disabled, the spec can still be served to
+// authenticated users at a separate, non-default path.
+fn setup_protected_openapi_json(path: &str, api: OpenApi) -> Router<Arc<AppState>> {
+    Router::new().route(
+        path,
+        get(move |_: AuthenticatedUser| async move { Json(api) }),
+    )
 }
 
 async fn start_server(router: Router, port: u16) -> anyhow::Result<()> {
@@ -107,31 +385,113 @@ async fn start_server(router: Router, port: u16) -> anyhow::Result<()> {
 
     tracing::info!("🚀 Server listening on {}", &address);
 
-    axum::serve(listener, router)
-        .await
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
         .map_err(|e| anyhow::anyhow!("Server error: {}", e))?;
     Ok(())
 }
 
-async fn initialize_session_layer() -> anyhow::Result<SessionManagerLayer<RedisStore<Pool>>> {
-    let redis_url = env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string());
-    let config = Config::from_url(&redis_url)?;
-    let pool = Pool::new(config, None, None, None, 6)?;
-    pool.connect();
-    pool.wait_for_connect().await?;
-    let session_store = RedisStore::new(pool);
+// Plain HTTP listener for orchestrator probes, kept apart from user traffic
+// and from the main router's middleware stack.
+async fn spawn_management_server(app_state: Arc<AppState>, port: u16) -> anyhow::Result<()> {
+    let address = format!("{}:{}", Ipv4Addr::UNSPECIFIED, port);
+    let listener = TcpListener::bind(&address).await.map_err(|e| {
+        anyhow::anyhow!("Failed to bind management address {}: {}", address, e)
+    })?;
+    let router = Router::new()
+        .route("/health/live", get(health_handler::liveness))
+        .route("/health/ready", get(health_handler::readiness))
+        .with_state(app_state);
+
+    tracing::info!("Management server listening on {}", &address);
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, router).await {
+            tracing::error!("Management server error: {}", e);
+        }
+    });
+    Ok(())
+}
+
+fn connect_session_store(pool: Pool) -> anyhow::Result<AppSessionStore> {
+    Ok(ResilientSessionStore::new(EncryptingSessionStore::new(
+        RedisStore::new(pool),
+        SessionCipher::from_env()?,
+    )))
+}
+
+fn debug_errors_enabled() -> anyhow::Result<bool> {
+    let requested = env_flag("DEBUG_ERRORS", false)?;
+    let production = is_production();
+    if requested && production {
+        tracing::warn!("DEBUG_ERRORS is ignored unless ENVIRONMENT is development, dev, local or test");
+    }
+    Ok(requested && !production)
+}
+
+fn session_cookie_name() -> anyhow::Result<String> {
+    let cookie_name = env_or(
+        "SESSION_COOKIE_NAME",
+        concat!(env!("CARGO_PKG_NAME"), "_session"),
+    );
+    validate_cookie_name(&cookie_name)?;
+    Ok(cookie_name)
+}
+
+fn initialize_session_layer(
+    session_store: AppSessionStore,
+    cookie_name: String,
+) -> anyhow::Result<SessionManagerLayer<AppSessionStore>> {
+    let cookie_path = env_or("SESSION_COOKIE_PATH", "/");
+    validate_cookie_path(&cookie_path)?;
+    let declared_production = is_declared_production();
+    let secure = env_flag("SESSION_SECURE", declared_production)?;
+    if !secure
+        && declared_production
+        && !env_flag("SESSION_ALLOW_INSECURE_IN_PRODUCTION", false)?
+    {
+        anyhow::bail!(
+            "SESSION_SECURE=false is refused when ENVIRONMENT declares production; \
+             set SESSION_ALLOW_INSECURE_IN_PRODUCTION=true to override"
+        );
+    }
     let session_layer = SessionManagerLayer::new(session_store)
-        .with_secure(false)
+        .with_name(cookie_name)
+        .with_path(cookie_path)
+        .with_secure(secure)
         .with_same_site(SameSite::Lax)
-        .with_expiry(Expiry::OnInactivity(SessionDuration::days(1)));
+        .with_expiry(Expiry::OnInactivity(SESSION_INACTIVITY));
     Ok(session_layer)
 }
 
+fn validate_cookie_name(name: &str) -> anyhow::Result<()> {
+    const SEPARATORS: &str = "()<>@,;:\\\"/[]?={}";
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_graphic() && !SEPARATORS.contains(c))
+    {
+        anyhow::bail!("SESSION_COOKIE_NAME {:?} is not a valid cookie name", name);
+    }
+    Ok(())
+}
+
+fn validate_cookie_path(path: &str) -> anyhow::Result<()> {
+    if !path.starts_with('/') || path.chars().any(|c| c == ';' || c.is_ascii_control()) {
+        anyhow::bail!("SESSION_COOKIE_PATH {:?} must be an absolute path", path);
+    }
+    Ok(())
+}
+
 fn init_observability() {
     registry()
         .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| format!("{}=debug", env!("CARGO_CRATE_NAME")).into()),
+            env_var("RUST_LOG")
+                .and_then(|filter| tracing_subscriber::EnvFilter::try_new(filter).ok())
+                .unwrap_or_else(|| format!("{}=debug", env!("CARGO_CRATE_NAME")).into()),
         )
         .with(tracing_subscriber::fmt::layer())
         .init();
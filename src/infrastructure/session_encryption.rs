@@ -0,0 +1,169 @@
+/*
+ * Copyright 2025 uuhnaut69
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *    http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::infrastructure::config::env_or_file;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+use tower_sessions::SessionStore;
+use tower_sessions::session::{Id, Record};
+use tower_sessions::session_store::{Error, Result};
+
+const ENCRYPTED_DATA_KEY: &str = "enc";
+const NONCE_LENGTH: usize = 12;
+
+/// AES-256-GCM keys for session data. The first key encrypts; every key is
+/// tried on decrypt, so a new key can be prepended and old ones dropped once
+/// their sessions have expired.
+pub struct SessionCipher {
+    keys: Vec<Aes256Gcm>,
+}
+
+impl SessionCipher {
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Some(value) = env_or_file("SESSION_ENCRYPTION_KEYS")? else {
+            return Ok(None);
+        };
+        let keys = value
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                let bytes = STANDARD
+                    .decode(key)
+                    .map_err(|e| anyhow::anyhow!("Invalid SESSION_ENCRYPTION_KEYS entry: {}", e))?;
+                if bytes.len() != 32 {
+                    anyhow::bail!(
+                        "SESSION_ENCRYPTION_KEYS entries must be 32 bytes, base64 encoded"
+                    );
+                }
+                Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&bytes)))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if keys.is_empty() {
+            anyhow::bail!("SESSION_ENCRYPTION_KEYS is set but contains no keys");
+        }
+        Ok(Some(Self { keys }))
+    }
+
+    fn encrypt(&self, record: &Record) -> Result<Record> {
+        let plaintext =
+            serde_json::to_vec(&record.data).map_err(|e| Error::Encode(e.to_string()))?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self.keys[0]
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|e| Error::Encode(e.to_string()))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        let mut encrypted = record.clone();
+        encrypted.data.clear();
+        encrypted.data.insert(
+            ENCRYPTED_DATA_KEY.to_string(),
+            Value::String(STANDARD.encode(payload)),
+        );
+        Ok(encrypted)
+    }
+
+    // Plaintext records are rejected rather than passed through, the session
+    // extractor then discards them like any other undecodable session.
+    fn decrypt(&self, mut record: Record) -> Result<Record> {
+        let payload = match record.data.get(ENCRYPTED_DATA_KEY) {
+            Some(Value::String(payload)) if record.data.len() == 1 => STANDARD
+                .decode(payload)
+                .map_err(|e| Error::Decode(e.to_string()))?,
+            _ => return Err(Error::Decode("session data is not encrypted".to_string())),
+        };
+        if payload.len() < NONCE_LENGTH {
+            return Err(Error::Decode(
+                "encrypted session data is truncated".to_string(),
+            ));
+        }
+
+        let (nonce, ciphertext) = payload.split_at(NONCE_LENGTH);
+        let nonce = Nonce::from_slice(nonce);
+        let plaintext = self
+            .keys
+            .iter()
+            .find_map(|key| key.decrypt(nonce, ciphertext).ok())
+            .ok_or_else(|| Error::Decode("no session key decrypts the data".to_string()))?;
+        record.data =
+            serde_json::from_slice(&plaintext).map_err(|e| Error::Decode(e.to_string()))?;
+        Ok(record)
+    }
+}
+
+/// Session store wrapper encrypting record data at rest. Without a cipher it
+/// passes records through unchanged.
+#[derive(Clone)]
+pub struct EncryptingSessionStore<S> {
+    inner: S,
+    cipher: Option<Arc<SessionCipher>>,
+}
+
+impl<S> EncryptingSessionStore<S> {
+    pub fn new(inner: S, cipher: Option<SessionCipher>) -> Self {
+        Self {
+            inner,
+            cipher: cipher.map(Arc::new),
+        }
+    }
+}
+
+impl<S: fmt::Debug> fmt::Debug for EncryptingSessionStore<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptingSessionStore")
+            .field("inner", &self.inner)
+            .field("encrypted", &self.cipher.is_some())
+            .finish()
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SessionStore> SessionStore for EncryptingSessionStore<S> {
+    async fn create(&self, record: &mut Record) -> Result<()> {
+        let Some(cipher) = &self.cipher else {
+            return self.inner.create(record).await;
+        };
+        let mut encrypted = cipher.encrypt(record)?;
+        self.inner.create(&mut encrypted).await?;
+        record.id = encrypted.id;
+        Ok(())
+    }
+
+    async fn save(&self, record: &Record) -> Result<()> {
+        match &self.cipher {
+            Some(cipher) => self.inner.save(&cipher.encrypt(record)?).await,
+            None => self.inner.save(record).await,
+        }
+    }
+
+    async fn load(&self, session_id: &Id) -> Result<Option<Record>> {
+        let record = self.inner.load(session_id).await?;
+        match (&self.cipher, record) {
+            (Some(cipher), Some(record)) => cipher.decrypt(record).map(Some),
+            (_, record) => Ok(record),
+        }
+    }
+
+    async fn delete(&self, session_id: &Id) -> Result<()> {
+        self.inner.delete(session_id).await
+    }
+}
@@ -14,11 +14,13 @@
  * limitations under the License.
  */
 use dotenvy::dotenv;
-use rustapi::infrastructure::server::initialize_server;
+use rustapi::infrastructure::config::load_config_file;
+use rustapi::infrastructure::server::{build_runtime, initialize_server};
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
+// The runtime is built by hand because its worker count is configuration.
+fn main() -> anyhow::Result<()> {
     dotenv().ok();
-    initialize_server().await?;
+    load_config_file()?;
+    build_runtime()?.block_on(initialize_server())?;
     Ok(())
 }